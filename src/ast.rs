@@ -0,0 +1,369 @@
+use chumsky::input::ValueInput;
+use chumsky::prelude::*;
+
+use crate::lexer::Token;
+
+pub type Span = SimpleSpan<usize>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prim { I8, I16, I32, I64, U8, U16, U32, U64, F32, Bool }
+
+#[derive(Clone, Debug)]
+pub enum Type<'a> {
+    Void,
+    Primitive(Prim),
+    Named(&'a str),
+    Ptr(Box<Type<'a>>),
+    Slice(Box<Type<'a>>),
+    Array(Box<Type<'a>>, i32),
+}
+
+#[derive(Clone, Debug)]
+pub struct Param<'a> {
+    pub outward_name: Option<&'a str>,
+    pub name: &'a str,
+    pub ty: Type<'a>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FunctionItem<'a> {
+    pub ret: Type<'a>,
+    pub name: &'a str,
+    pub params: Vec<Param<'a>>,
+    pub body: Vec<Stmt<'a>>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum Item<'a> {
+    Struct { name: &'a str, fields: Vec<(&'a str, Type<'a>)>, span: Span },
+    Union { name: &'a str, variants: Vec<(&'a str, Type<'a>)>, span: Span },
+    Enum { name: &'a str, variants: Vec<(&'a str, Option<i64>)>, span: Span },
+    Function(FunctionItem<'a>),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum BinOp { Add, Sub, Mul, Div, Mod, And, Or, Xor, LogicAnd, LogicOr, LogicXor, Eq, Ne, Gt, Ge, Lt, Le }
+
+#[derive(Clone, Copy, Debug)]
+pub enum UnaryOp { AddressOf, Deref, Negate, Not }
+
+/// A numeric literal suffix, e.g. the `u8` in `100u8`.
+#[derive(Clone, Copy, Debug)]
+pub struct NumSuffix { pub bits: u32, pub signed: bool }
+
+#[derive(Clone, Debug)]
+pub enum Expr<'a> {
+    Var(&'a str, Span),
+    Num { value: i64, suffix: Option<NumSuffix>, span: Span },
+    Str(String, Span),
+    /// `---`
+    Uninit(Span),
+    Unit(Span),
+    /// `Name { field = expr, ... }`
+    StructLiteral { ty: &'a str, fields: Vec<(&'a str, Option<Expr<'a>>)>, span: Span },
+    FieldAccess(Box<Expr<'a>>, &'a str, Span),
+    PathAccess(&'a str, &'a str, Span),
+    Call { name: &'a str, args: Vec<(&'a str, Expr<'a>)>, span: Span },
+    Return(Option<Box<Expr<'a>>>, Span),
+    Break(Span),
+    Continue(Span),
+    BinOp(Box<Expr<'a>>, BinOp, Box<Expr<'a>>, Span),
+    UnaryOp(UnaryOp, Box<Expr<'a>>, Span),
+}
+
+#[derive(Clone, Debug)]
+pub enum Stmt<'a> {
+    /// `<name> = <expr>;` (also covers first-use declarations, e.g. `i32 i = 0;`, where `ty` carries
+    /// the declared type so it can constrain the assigned value's inference)
+    Assign { name: &'a str, ty: Option<Type<'a>>, value: Expr<'a>, span: Span },
+    DerefAssign { ptr: Expr<'a>, value: Expr<'a>, span: Span },
+    FieldAssign { object: Expr<'a>, field: &'a str, value: Expr<'a>, span: Span },
+    Do(Expr<'a>),
+    Block(Vec<Stmt<'a>>, Span),
+    If { cond: Expr<'a>, block: Vec<Stmt<'a>>, else_block: Option<Vec<Stmt<'a>>>, span: Span },
+    Loop(Vec<Stmt<'a>>, Span),
+}
+
+impl std::fmt::Display for Item<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Item::Struct { name, fields, .. } => write!(f, "struct {name} {{ {} fields }}", fields.len()),
+            Item::Union { name, variants, .. } => write!(f, "union {name} {{ {} variants }}", variants.len()),
+            Item::Enum { name, variants, .. } => write!(f, "enum {name} {{ {} variants }}", variants.len()),
+            Item::Function(func) => write!(f, "fn {}({} params)", func.name, func.params.len()),
+        }
+    }
+}
+
+fn type_parser<'a, I>() -> impl Parser<'a, I, Type<'a>, extra::Err<Rich<'a, Token<'a>, Span>>> + Clone
+where
+    I: ValueInput<'a, Token = Token<'a>, Span = Span>,
+{
+    let prim = select! {
+        Token::Ident("void") => Type::Void,
+        Token::Ident("i8") => Type::Primitive(Prim::I8),
+        Token::Ident("i16") => Type::Primitive(Prim::I16),
+        Token::Ident("i32") => Type::Primitive(Prim::I32),
+        Token::Ident("i64") => Type::Primitive(Prim::I64),
+        Token::Ident("u8") => Type::Primitive(Prim::U8),
+        Token::Ident("u16") => Type::Primitive(Prim::U16),
+        Token::Ident("u32") => Type::Primitive(Prim::U32),
+        Token::Ident("u64") => Type::Primitive(Prim::U64),
+        Token::Ident("f32") => Type::Primitive(Prim::F32),
+        Token::Ident("bool") => Type::Primitive(Prim::Bool),
+        Token::Ident(name) => Type::Named(name),
+    };
+
+    prim.then(
+        choice((
+            just(Token::Ctrl('[')).then(just(Token::Ctrl(']'))).to(None),
+        ))
+        .repeated()
+        .collect::<Vec<_>>(),
+    )
+    .map(|(base, suffixes)| {
+        suffixes.into_iter().fold(base, |ty, _: Option<()>| Type::Slice(Box::new(ty)))
+    })
+}
+
+/// Builds the full expression-precedence chain (postfix field access, unary, product, sum, compare,
+/// logical and/or/xor) on top of `inner`, the parser to use for every *nested* sub-expression
+/// position (call/struct-literal-field values, `return`'s operand, parenthesized groups).
+///
+/// `allow_struct_literal` gates whether a bare `ident { ... }` is itself offered as an atom here.
+/// It's turned off for `if` condition position: `if flag { x = 1 }` would otherwise parse as
+/// `if (flag { x = 1 })` with no block left for the `if`, the same ambiguity C resolves by banning
+/// struct literals in a condition. Nested positions (parens, call args, etc.) stay unambiguous since
+/// they're delimited, so they keep using the full `inner` parser regardless.
+fn expr_chain<'a, I, E>(inner: E, allow_struct_literal: bool) -> impl Parser<'a, I, Expr<'a>, extra::Err<Rich<'a, Token<'a>, Span>>> + Clone
+where
+    I: ValueInput<'a, Token = Token<'a>, Span = Span>,
+    E: Parser<'a, I, Expr<'a>, extra::Err<Rich<'a, Token<'a>, Span>>> + Clone + 'a,
+{
+    let ident = select! { Token::Ident(i) => i };
+
+    let num = select! { Token::Num(n, suffix) => (n, suffix) }.try_map(|(n, suffix), span| {
+        // `value` just carries the literal's raw bits (interp/codegen treat it as an `i64` register
+        // value regardless of signedness); a magnitude up to `u64::MAX` still fits those bits, it just
+        // needs parsing as `u64` first since e.g. `18446744073709551615` overflows `i64::MAX`.
+        let value = n.parse::<i64>().or_else(|_| n.parse::<u64>().map(|n| n as i64))
+            .map_err(|_| Rich::custom(span, format!("`{n}` is not a valid integer literal (it may be too large, or a float literal isn't supported here)")))?;
+        let suffix = suffix.and_then(|s| match s {
+            "i8" => Some(NumSuffix { bits: 8, signed: true }),
+            "i16" => Some(NumSuffix { bits: 16, signed: true }),
+            "i32" => Some(NumSuffix { bits: 32, signed: true }),
+            "i64" => Some(NumSuffix { bits: 64, signed: true }),
+            "u8" => Some(NumSuffix { bits: 8, signed: false }),
+            "u16" => Some(NumSuffix { bits: 16, signed: false }),
+            "u32" => Some(NumSuffix { bits: 32, signed: false }),
+            "u64" => Some(NumSuffix { bits: 64, signed: false }),
+            _ => None,
+        });
+        Ok(Expr::Num { value, suffix, span })
+    });
+
+    let str_lit = select! { Token::Str(s) => s };
+
+    let struct_fields = ident
+        .then_ignore(just(Token::Ctrl('=')))
+        .then(inner.clone())
+        .map(|(name, value)| (name, Some(value)))
+        .or(ident.then_ignore(just(Token::Op("---"))).map(|name| (name, None)))
+        .separated_by(just(Token::Ctrl(',')))
+        .allow_trailing()
+        .collect::<Vec<_>>();
+
+    let struct_literal = ident
+        .then_ignore(just(Token::Ctrl('{')))
+        .then(struct_fields)
+        .then_ignore(just(Token::Ctrl('}')))
+        .map_with(|(ty, fields), e| Expr::StructLiteral { ty, fields, span: e.span() });
+
+    let call_args = ident
+        .then_ignore(just(Token::Ctrl('=')))
+        .then(inner.clone())
+        .separated_by(just(Token::Ctrl(',')))
+        .allow_trailing()
+        .collect::<Vec<_>>();
+
+    let call = ident
+        .then(call_args.delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')'))))
+        .map_with(|(name, args), e| Expr::Call { name, args, span: e.span() });
+
+    let rest = choice((
+        just(Token::Op("---")).map_with(|_, e| Expr::Uninit(e.span())),
+        just(Token::Ctrl('(')).then(just(Token::Ctrl(')'))).map_with(|_, e| Expr::Unit(e.span())),
+        num,
+        str_lit.map_with(|s, e| Expr::Str(s, e.span())),
+        just(Token::Ident("break")).map_with(|_, e| Expr::Break(e.span())),
+        just(Token::Ident("continue")).map_with(|_, e| Expr::Continue(e.span())),
+        just(Token::Ident("return"))
+            .ignore_then(inner.clone().or_not())
+            .map_with(|v, e| Expr::Return(v.map(Box::new), e.span())),
+        call,
+        ident.map_with(|i, e| Expr::Var(i, e.span())),
+        inner.clone().delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')'))),
+    ))
+    .boxed();
+
+    let atom = if allow_struct_literal { choice((struct_literal, rest)).boxed() } else { rest };
+
+    let postfix = atom.foldl_with(
+        just(Token::Ctrl('.')).ignore_then(ident).repeated(),
+        |e, field, extra| Expr::FieldAccess(Box::new(e), field, extra.span()),
+    );
+
+    let unary = choice((
+        just(Token::Ctrl('&')).ignore_then(postfix.clone()).map_with(|e, ex| Expr::UnaryOp(UnaryOp::AddressOf, Box::new(e), ex.span())),
+        just(Token::Ctrl('*')).ignore_then(postfix.clone()).map_with(|e, ex| Expr::UnaryOp(UnaryOp::Deref, Box::new(e), ex.span())),
+        just(Token::Ctrl('-')).ignore_then(postfix.clone()).map_with(|e, ex| Expr::UnaryOp(UnaryOp::Negate, Box::new(e), ex.span())),
+        just(Token::Ctrl('!')).ignore_then(postfix.clone()).map_with(|e, ex| Expr::UnaryOp(UnaryOp::Not, Box::new(e), ex.span())),
+        postfix,
+    ));
+
+    let product = unary.clone().foldl_with(
+        choice((
+            just(Token::Ctrl('*')).to(BinOp::Mul),
+            just(Token::Ctrl('/')).to(BinOp::Div),
+            just(Token::Ctrl('%')).to(BinOp::Mod),
+        ))
+        .then(unary)
+        .repeated(),
+        |a, (op, b), e| Expr::BinOp(Box::new(a), op, Box::new(b), e.span()),
+    );
+
+    let sum = product.clone().foldl_with(
+        choice((just(Token::Ctrl('+')).to(BinOp::Add), just(Token::Ctrl('-')).to(BinOp::Sub)))
+            .then(product)
+            .repeated(),
+        |a, (op, b), e| Expr::BinOp(Box::new(a), op, Box::new(b), e.span()),
+    );
+
+    let compare = sum.clone().foldl_with(
+        choice((
+            just(Token::Op("==")).to(BinOp::Eq),
+            just(Token::Op("!=")).to(BinOp::Ne),
+            just(Token::Op(">=")).to(BinOp::Ge),
+            just(Token::Op("<=")).to(BinOp::Le),
+            just(Token::Ctrl('>')).to(BinOp::Gt),
+            just(Token::Ctrl('<')).to(BinOp::Lt),
+        ))
+        .then(sum)
+        .repeated(),
+        |a, (op, b), e| Expr::BinOp(Box::new(a), op, Box::new(b), e.span()),
+    );
+
+    compare.clone().foldl_with(
+        choice((
+            just(Token::Op("&&")).to(BinOp::LogicAnd),
+            just(Token::Op("||")).to(BinOp::LogicOr),
+            just(Token::Op("^^")).to(BinOp::LogicXor),
+        ))
+        .then(compare)
+        .repeated(),
+        |a, (op, b), e| Expr::BinOp(Box::new(a), op, Box::new(b), e.span()),
+    )
+}
+
+/// Parses a full cflat translation unit into a list of top-level items.
+pub fn parser<'a, I>() -> impl Parser<'a, I, Vec<Item<'a>>, extra::Err<Rich<'a, Token<'a>, Span>>>
+where
+    I: ValueInput<'a, Token = Token<'a>, Span = Span>,
+{
+    let ident = select! { Token::Ident(i) => i };
+
+    let expr = recursive(|expr| expr_chain(expr, true));
+    // Used only for `if` conditions, where a bare struct literal would be ambiguous with the
+    // block that follows; everything nested inside (parens, call args, ...) still goes through the
+    // full `expr` above via `inner`.
+    let cond_expr = expr_chain(expr.clone(), false);
+
+    let stmt = recursive(|stmt| {
+        let block = stmt
+            .clone()
+            .repeated()
+            .collect::<Vec<_>>()
+            .delimited_by(just(Token::Ctrl('{')), just(Token::Ctrl('}')));
+
+        let assign = ident
+            .then_ignore(just(Token::Ctrl('=')))
+            .then(expr.clone())
+            .then_ignore(just(Token::Ctrl(';')))
+            .map_with(|(name, value), e| Stmt::Assign { name, ty: None, value, span: e.span() });
+
+        let var_decl = type_parser()
+            .then(ident)
+            .then_ignore(just(Token::Ctrl('=')))
+            .then(expr.clone())
+            .then_ignore(just(Token::Ctrl(';')))
+            .map_with(|((ty, name), value), e| Stmt::Assign { name, ty: Some(ty), value, span: e.span() });
+
+        let deref_assign = just(Token::Ctrl('*'))
+            .ignore_then(expr.clone())
+            .then_ignore(just(Token::Ctrl('=')))
+            .then(expr.clone())
+            .then_ignore(just(Token::Ctrl(';')))
+            .map_with(|(ptr, value), e| Stmt::DerefAssign { ptr, value, span: e.span() });
+
+        let if_stmt = just(Token::Ident("if"))
+            .ignore_then(cond_expr.clone())
+            .then(block.clone())
+            .then(
+                just(Token::Ident("else"))
+                    .ignore_then(
+                        block
+                            .clone()
+                            .map(Some)
+                            .or(stmt.clone().map(|s| Some(vec![s]))),
+                    )
+                    .or_not(),
+            )
+            .map_with(|((cond, block), else_block), e| Stmt::If { cond, block, else_block: else_block.flatten(), span: e.span() });
+
+        let loop_stmt = just(Token::Ident("loop"))
+            .ignore_then(block.clone())
+            .map_with(|block, e| Stmt::Loop(block, e.span()));
+
+        let do_stmt = expr
+            .clone()
+            .then_ignore(just(Token::Ctrl(';')).or_not())
+            .map(Stmt::Do);
+
+        choice((var_decl, assign, deref_assign, if_stmt, loop_stmt, block.clone().map_with(|b, e| Stmt::Block(b, e.span())), do_stmt))
+    });
+
+    let field = type_parser().then(ident).then_ignore(just(Token::Ctrl(';'))).map(|(ty, name)| (name, ty));
+
+    let struct_item = just(Token::Ident("struct"))
+        .ignore_then(ident)
+        .then(field.clone().repeated().collect::<Vec<_>>().delimited_by(just(Token::Ctrl('{')), just(Token::Ctrl('}'))))
+        .map_with(|(name, fields), e| Item::Struct { name, fields, span: e.span() });
+
+    let union_item = just(Token::Ident("union"))
+        .ignore_then(ident)
+        .then(field.repeated().collect::<Vec<_>>().delimited_by(just(Token::Ctrl('{')), just(Token::Ctrl('}'))))
+        .map_with(|(name, variants), e| Item::Union { name, variants, span: e.span() });
+
+    let param = ident
+        .then_ignore(just(Token::Ctrl(':')))
+        .then(type_parser())
+        .then(ident)
+        .map(|((outward, ty), name)| Param { outward_name: Some(outward), name, ty });
+
+    let function_item = type_parser()
+        .then(ident)
+        .then(param.separated_by(just(Token::Ctrl(','))).allow_trailing().collect::<Vec<_>>().delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')'))))
+        .then(
+            stmt.clone()
+                .repeated()
+                .collect::<Vec<_>>()
+                .delimited_by(just(Token::Ctrl('{')), just(Token::Ctrl('}'))),
+        )
+        .map_with(|(((ret, name), params), body), e| Item::Function(FunctionItem { ret, name, params, body, span: e.span() }));
+
+    choice((struct_item, union_item, function_item))
+        .repeated()
+        .collect()
+        .then_ignore(end())
+}
@@ -0,0 +1,27 @@
+use chumsky::error::Rich;
+
+/// Pretty-prints a batch of parse/type errors against the original source text.
+///
+/// This is intentionally simple (no colours, no multi-line source snippets):
+/// it just reports the offending span and the error's own message.
+pub fn show_errs<'a, T: std::fmt::Display>(src: &'a str, filename: &str, errs: Vec<Rich<'a, T>>) {
+    for err in &errs {
+        let span = err.span();
+        let (line, col) = line_col(src, span.start);
+        eprintln!("{filename}:{line}:{col}: error: {err}");
+    }
+}
+
+fn line_col(src: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for c in src[..offset.min(src.len())].chars() {
+        if c == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
@@ -0,0 +1,596 @@
+//! Lowers a typechecked [`Program`] into a flat register-machine bytecode and runs it.
+//!
+//! Register conventions (256 registers total):
+//!
+//! | registers   | role                                   |
+//! |-------------|-----------------------------------------|
+//! | `r0`        | hard-wired zero                          |
+//! | `r1`, `r2`  | return value(s)                          |
+//! | `r2..r11`   | argument passing (overlaps `r2`)         |
+//! | `r12..r30`  | caller-saved general purpose              |
+//! | `r31`       | return address                           |
+//! | `r32..r253` | callee-saved general purpose              |
+//! | `r254`      | stack pointer                            |
+
+use std::collections::HashMap;
+use std::iter::Cycle;
+use std::ops::Range;
+
+use super::{BinOp, Block, Expr, Function, FuncKey, LiteralKey, Program, Statement, UnaryOp, Value, Var};
+
+pub const NUM_REGS: usize = 256;
+pub const R_ZERO: u8 = 0;
+pub const R_RET: Range<u8> = 1..3;
+pub const R_ARGS: Range<u8> = 2..12;
+pub const R_CALLER_SAVED: Range<u8> = 12..31;
+pub const R_RETURN_ADDR: u8 = 31;
+pub const R_CALLEE_SAVED: Range<u8> = 32..254;
+pub const R_STACK_PTR: u8 = 254;
+/// The range the allocator hands out registers from (caller + callee saved); `RegAlloc::alloc`
+/// skips `r31` inside it.
+const R_GENERAL: Range<u8> = 12..254;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instr {
+    LoadImm(u8, i64),
+    LoadLiteral(u8, LiteralKey),
+    Move(u8, u8),
+    BinOp(BinOp2, u8, u8, u8),
+    UnaryOp(UnaryOp2, u8, u8),
+    /// Spill: store a register's value to a stack slot.
+    StoreSlot(u32, u8),
+    /// Reload: load a stack slot back into a register.
+    LoadSlot(u8, u32),
+    Jump(usize),
+    /// Jumps to the target if the register holds zero (cflat has no dedicated bool register class).
+    JumpIfZero(u8, usize),
+    Call(FuncKey),
+    Return,
+}
+
+// `BinOp`/`UnaryOp` in `super` don't implement `Copy` (they don't need to elsewhere), so codegen
+// keeps its own `Copy` mirrors to store directly in `Instr`.
+#[derive(Debug, Clone, Copy)]
+pub enum BinOp2 { Add, Sub, Mul, Div, Mod, And, Or, Xor, LogicAnd, LogicOr, LogicXor, Eq, Ne, Gt, Ge, Lt, Le }
+#[derive(Debug, Clone, Copy)]
+pub enum UnaryOp2 { AddressOf, Deref, Negate, Not }
+
+fn binop2(op: &BinOp) -> BinOp2 {
+    match op {
+        BinOp::Add => BinOp2::Add,
+        BinOp::Sub => BinOp2::Sub,
+        BinOp::Mul => BinOp2::Mul,
+        BinOp::Div => BinOp2::Div,
+        BinOp::Mod => BinOp2::Mod,
+        BinOp::And => BinOp2::And,
+        BinOp::Or => BinOp2::Or,
+        BinOp::Xor => BinOp2::Xor,
+        BinOp::LogicAnd => BinOp2::LogicAnd,
+        BinOp::LogicOr => BinOp2::LogicOr,
+        BinOp::LogicXor => BinOp2::LogicXor,
+        BinOp::Eq => BinOp2::Eq,
+        BinOp::Ne => BinOp2::Ne,
+        BinOp::Gt => BinOp2::Gt,
+        BinOp::Ge => BinOp2::Ge,
+        BinOp::Lt => BinOp2::Lt,
+        BinOp::Le => BinOp2::Le,
+    }
+}
+
+fn unaryop2(op: &UnaryOp) -> UnaryOp2 {
+    match op {
+        UnaryOp::AddressOf => UnaryOp2::AddressOf,
+        UnaryOp::Deref => UnaryOp2::Deref,
+        UnaryOp::Negate => UnaryOp2::Negate,
+        UnaryOp::Not => UnaryOp2::Not,
+    }
+}
+
+/// Either a real source-level [`Var`] or a codegen-introduced temporary (for `BinOp`/`UnaryOp`/
+/// `FuncCall` results and literal/immediate materialization); both get allocated a register the
+/// same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Loc {
+    Var(Var),
+    Temp(u32),
+}
+
+/// Per-`Function` register allocator: tracks which [`Loc`] lives in which register, spilling to a
+/// stack slot and round-robining through the general-purpose range when registers run out.
+struct RegAlloc {
+    /// `regs[r]` is the variable/temporary currently occupying register `r`, if any.
+    regs: [Option<Loc>; NUM_REGS],
+    /// Registers this function has used at least once, so its prologue/epilogue knows which
+    /// callee-saved registers to preserve.
+    used: [bool; NUM_REGS],
+    loc_reg: HashMap<Loc, u8>,
+    spill_slot: HashMap<Loc, u32>,
+    next_slot: u32,
+    next_temp: u32,
+    cursor: Cycle<Range<u8>>,
+}
+
+impl RegAlloc {
+    fn new() -> Self {
+        RegAlloc {
+            regs: [None; NUM_REGS],
+            used: [false; NUM_REGS],
+            loc_reg: HashMap::new(),
+            spill_slot: HashMap::new(),
+            next_slot: 0,
+            next_temp: 0,
+            cursor: R_GENERAL.cycle(),
+        }
+    }
+
+    fn new_temp(&mut self) -> Loc {
+        let t = Loc::Temp(self.next_temp);
+        self.next_temp += 1;
+        t
+    }
+
+    fn slot_for(&mut self, loc: Loc) -> u32 {
+        *self.spill_slot.entry(loc).or_insert_with(|| {
+            let slot = self.next_slot;
+            self.next_slot += 1;
+            slot
+        })
+    }
+
+    /// A stack slot not tied to any `Loc`, for saving/restoring a callee-saved register around a
+    /// function body rather than a particular variable or temporary.
+    fn fresh_slot(&mut self) -> u32 {
+        let slot = self.next_slot;
+        self.next_slot += 1;
+        slot
+    }
+
+    /// Finds a free register, spilling the round-robin victim if the general range is full.
+    ///
+    /// `protect` lists registers currently holding an operand the caller has already computed and
+    /// still needs to read once this call returns (e.g. `l` while allocating a destination for
+    /// `l op r`, or `l` while computing an `l op r`'s `r` after `l`). Without this, the round-robin
+    /// cursor could pick exactly one of those registers as its victim, silently spilling out a value
+    /// the instruction being built is about to read, before it ever gets read.
+    fn alloc(&mut self, loc: Loc, out: &mut Vec<Instr>, protect: &[u8]) -> u8 {
+        if let Some(&r) = self.loc_reg.get(&loc) {
+            return r;
+        }
+        let free = R_GENERAL.clone().find(|r| *r != R_RETURN_ADDR && self.regs[*r as usize].is_none());
+        let reg = match free {
+            Some(r) => r,
+            None => {
+                // Evict the next victim in round-robin order that isn't protected (or `r31`),
+                // spilling it to its stack slot. Bounded by the general range's size so a
+                // (should-be-impossible) fully-protected range can't spin forever.
+                let mut victim = self.cursor.next().expect("R_GENERAL is non-empty");
+                let mut remaining = (R_GENERAL.end - R_GENERAL.start) as usize;
+                while (victim == R_RETURN_ADDR || protect.contains(&victim)) && remaining > 1 {
+                    victim = self.cursor.next().expect("R_GENERAL is non-empty");
+                    remaining -= 1;
+                }
+                if let Some(victim_loc) = self.regs[victim as usize].take() {
+                    let slot = self.slot_for(victim_loc);
+                    out.push(Instr::StoreSlot(slot, victim));
+                    self.loc_reg.remove(&victim_loc);
+                }
+                victim
+            }
+        };
+        self.regs[reg as usize] = Some(loc);
+        self.loc_reg.insert(loc, reg);
+        self.used[reg as usize] = true;
+        reg
+    }
+
+    /// Directly assigns `loc` to `reg`, without consulting the general range or the spill cursor.
+    /// Used to seed parameter registers (`r2..`) at function entry, per the calling convention.
+    fn bind(&mut self, loc: Loc, reg: u8) {
+        self.regs[reg as usize] = Some(loc);
+        self.loc_reg.insert(loc, reg);
+        self.used[reg as usize] = true;
+    }
+
+    /// Returns the register holding `var`, reloading it from its spill slot first if necessary. See
+    /// [`Self::alloc`] for what `protect` guards against.
+    fn reg_of(&mut self, var: Var, out: &mut Vec<Instr>, protect: &[u8]) -> u8 {
+        let loc = Loc::Var(var);
+        if let Some(&r) = self.loc_reg.get(&loc) {
+            return r;
+        }
+        let reg = self.alloc(loc, out, protect);
+        let slot = self.slot_for(loc);
+        out.push(Instr::LoadSlot(reg, slot));
+        reg
+    }
+}
+
+/// The compiled bytecode for every function in a [`Program`], keyed the same way as `Program::functions`.
+pub struct CodeUnit {
+    pub functions: HashMap<FuncKey, Vec<Instr>>,
+    pub entry: FuncKey,
+}
+
+impl<'a> Program<'a> {
+    /// Compiles every function down to register-machine bytecode. `main` is used as the entry point.
+    pub fn codegen(&self) -> CodeUnit {
+        let entry = *self.function_names.get("main").expect("program has a `main` function");
+        let mut functions = HashMap::new();
+        for key in self.functions.keys() {
+            functions.insert(key, codegen_function(self, &self.functions[key]));
+        }
+        CodeUnit { functions, entry }
+    }
+}
+
+fn codegen_function<'a>(program: &Program<'a>, func: &Function<'a>) -> Vec<Instr> {
+    let mut body = Vec::new();
+    let mut alloc = RegAlloc::new();
+    // The caller places argument `i` in `R_ARGS.start + i`; bind each parameter's `Var` straight to
+    // that register so reads of it inside the body don't need a separate prologue move.
+    for (i, &param) in func.param_vars.iter().enumerate().take((R_ARGS.end - R_ARGS.start) as usize) {
+        alloc.bind(Loc::Var(param), R_ARGS.start + i as u8);
+    }
+    let mut returns = Vec::new();
+    codegen_block(program, func, &mut alloc, &func.body, &mut body, &mut Vec::new(), &mut returns);
+
+    // Every callee-saved register this function actually wrote to (per `alloc.used`) holds a value
+    // the caller may still need live across this call, since the VM shares one register file across
+    // calls. Save it to a dedicated stack slot on entry and restore it on every return path, rather
+    // than just tracking `used` without acting on it.
+    let saved: Vec<(u8, u32)> = R_CALLEE_SAVED
+        .filter(|&r| alloc.used[r as usize])
+        .map(|r| (r, alloc.fresh_slot()))
+        .collect();
+
+    let mut out = Vec::with_capacity(saved.len() + body.len() + saved.len() + 1);
+    for &(r, slot) in &saved {
+        out.push(Instr::StoreSlot(slot, r));
+    }
+    // `body`'s own jump targets (If/Loop/break/continue) were already resolved to absolute indices
+    // within `body`; shift them by the prologue's length now that they're landing further out.
+    let offset = out.len();
+    out.extend(body.into_iter().map(|instr| shift_jump_target(instr, offset)));
+
+    let epilogue = out.len();
+    for &(r, slot) in &saved {
+        out.push(Instr::LoadSlot(r, slot));
+    }
+    out.push(Instr::Return);
+
+    // `Expr::Return` left a placeholder `Jump(0)` at each return site (recorded body-relative, so
+    // shift by the same offset) rather than emitting its own `Instr::Return` directly, so every
+    // return path runs the epilogue above instead of skipping the restores.
+    for fixup in returns {
+        out[fixup + offset] = Instr::Jump(epilogue);
+    }
+    out
+}
+
+/// Adds `offset` to a `Jump`/`JumpIfZero`'s target; used to relocate a function body's
+/// already-resolved jump targets after a prologue is prepended in front of it.
+fn shift_jump_target(instr: Instr, offset: usize) -> Instr {
+    match instr {
+        Instr::Jump(target) => Instr::Jump(target + offset),
+        Instr::JumpIfZero(r, target) => Instr::JumpIfZero(r, target + offset),
+        other => other,
+    }
+}
+
+/// One entry per enclosing `loop`: where its body starts (so `continue` can jump straight back to
+/// it) and the fixup list for its `break`s (patched to jump past the end once the loop is compiled).
+struct LoopCtx {
+    start: usize,
+    break_fixups: Vec<usize>,
+}
+
+fn codegen_block<'a>(
+    program: &Program<'a>,
+    func: &Function<'a>,
+    alloc: &mut RegAlloc,
+    block: &Block<'a>,
+    out: &mut Vec<Instr>,
+    loops: &mut Vec<LoopCtx>,
+    returns: &mut Vec<usize>,
+) {
+    for stmt in &block.stmts {
+        codegen_stmt(program, func, alloc, stmt, out, loops, returns);
+    }
+}
+
+fn codegen_stmt<'a>(
+    program: &Program<'a>,
+    func: &Function<'a>,
+    alloc: &mut RegAlloc,
+    stmt: &Statement<'a>,
+    out: &mut Vec<Instr>,
+    loops: &mut Vec<LoopCtx>,
+    returns: &mut Vec<usize>,
+) {
+    match stmt {
+        Statement::Assign(var, expr, _) => {
+            let src = codegen_expr(alloc, expr, out, loops, returns);
+            let dst = alloc.alloc(Loc::Var(*var), out, &[src]);
+            if dst != src {
+                out.push(Instr::Move(dst, src));
+            }
+        }
+        Statement::DerefAssign(ptr, value, _) => {
+            // No addressable heap modelled yet beyond registers; evaluated for side effects/typing.
+            codegen_expr(alloc, ptr, out, loops, returns);
+            codegen_expr(alloc, value, out, loops, returns);
+        }
+        Statement::FieldAssign { object, value, .. } => {
+            codegen_expr(alloc, object, out, loops, returns);
+            codegen_expr(alloc, value, out, loops, returns);
+        }
+        Statement::Do(expr) => {
+            codegen_expr(alloc, expr, out, loops, returns);
+        }
+        Statement::Block(block, _) => codegen_block(program, func, alloc, block, out, loops, returns),
+        Statement::If { cond, block, else_block, .. } => {
+            let cond_reg = codegen_expr(alloc, cond, out, loops, returns);
+            let jump_else = out.len();
+            out.push(Instr::JumpIfZero(cond_reg, 0));
+            codegen_block(program, func, alloc, block, out, loops, returns);
+            if let Some(else_block) = else_block {
+                let jump_end = out.len();
+                out.push(Instr::Jump(0));
+                let else_start = out.len();
+                codegen_block(program, func, alloc, else_block, out, loops, returns);
+                out[jump_else] = Instr::JumpIfZero(cond_reg, else_start);
+                let end = out.len();
+                out[jump_end] = Instr::Jump(end);
+            } else {
+                let end = out.len();
+                out[jump_else] = Instr::JumpIfZero(cond_reg, end);
+            }
+        }
+        Statement::Loop(block, _) => {
+            let start = out.len();
+            loops.push(LoopCtx { start, break_fixups: Vec::new() });
+            codegen_block(program, func, alloc, block, out, loops, returns);
+            out.push(Instr::Jump(start));
+            let end = out.len();
+            for fixup in loops.pop().expect("just pushed").break_fixups {
+                out[fixup] = Instr::Jump(end);
+            }
+        }
+    }
+}
+
+fn codegen_expr<'a>(
+    alloc: &mut RegAlloc,
+    expr: &Expr<'a>,
+    out: &mut Vec<Instr>,
+    loops: &mut Vec<LoopCtx>,
+    returns: &mut Vec<usize>,
+) -> u8 {
+    match expr {
+        Expr::Value(v) => codegen_value(alloc, v, out, &[]),
+        Expr::FieldAccess(object, _, _) => codegen_value(alloc, object, out, &[]),
+        Expr::PathAccess(_, _, _) => {
+            let t = alloc.new_temp();
+            let tmp = alloc.alloc(t, out, &[]);
+            out.push(Instr::LoadImm(tmp, 0));
+            tmp
+        }
+        Expr::StructLiteral { fields, .. } => {
+            // Aggregates aren't given a memory layout yet (there's no heap/stack model for them in
+            // this backend), so for now this just evaluates every field for its side effects and
+            // returns a placeholder register, the same way `PathAccess` does above.
+            for (_, value) in fields {
+                codegen_value(alloc, value, out, &[]);
+            }
+            let t = alloc.new_temp();
+            let tmp = alloc.alloc(t, out, &[]);
+            out.push(Instr::LoadImm(tmp, 0));
+            tmp
+        }
+        Expr::FuncCall(key, args, _) => {
+            // Spill every live caller-saved register across the call, since the callee may reuse or
+            // clobber its physical register, then move arguments into r2... Reload each spilled
+            // register right after the call returns, so it stays live in the same register for
+            // whoever reads it next.
+            let mut spilled = Vec::new();
+            for reg in R_CALLER_SAVED {
+                if let Some(loc) = alloc.regs[reg as usize] {
+                    let slot = alloc.slot_for(loc);
+                    out.push(Instr::StoreSlot(slot, reg));
+                    alloc.regs[reg as usize] = None;
+                    alloc.loc_reg.remove(&loc);
+                    spilled.push((loc, reg, slot));
+                }
+            }
+            // Protect each arg register already computed so evaluating a later, more complex arg
+            // (itself possibly spilling registers) can't evict a value this call still needs.
+            let mut arg_regs = Vec::new();
+            for (i, arg) in args.iter().enumerate().take((R_ARGS.end - R_ARGS.start) as usize) {
+                let src = codegen_value(alloc, arg, out, &arg_regs);
+                arg_regs.push(src);
+                let dst = R_ARGS.start + i as u8;
+                if dst != src {
+                    out.push(Instr::Move(dst, src));
+                }
+            }
+            out.push(Instr::Call(*key));
+            for (loc, reg, slot) in spilled {
+                out.push(Instr::LoadSlot(reg, slot));
+                alloc.regs[reg as usize] = Some(loc);
+                alloc.loc_reg.insert(loc, reg);
+            }
+            R_RET.start
+        }
+        Expr::Return(value, _) => {
+            if let Some(value) = value {
+                let src = codegen_value(alloc, value, out, &[]);
+                if src != R_RET.start {
+                    out.push(Instr::Move(R_RET.start, src));
+                }
+            }
+            // Jumps to the function's epilogue (which restores callee-saved registers and does the
+            // real `Instr::Return`) rather than returning directly here, so every return path runs
+            // it; patched once `codegen_function` knows where the epilogue lands.
+            returns.push(out.len());
+            out.push(Instr::Jump(0));
+            R_RET.start
+        }
+        Expr::Break(_) => {
+            let fixup = out.len();
+            out.push(Instr::Jump(0));
+            loops.last_mut().expect("`break` outside a loop should be rejected by typecheck").break_fixups.push(fixup);
+            R_ZERO
+        }
+        Expr::Continue(_) => {
+            // Jumps back to the start of the innermost loop, recorded in its `LoopCtx` when
+            // `codegen_stmt`'s `Loop` arm started compiling its body.
+            let start = loops.last().expect("`continue` outside a loop should be rejected by typecheck").start;
+            out.push(Instr::Jump(start));
+            R_ZERO
+        }
+        Expr::BinOp(lhs, op, rhs, _) => {
+            // `r`'s evaluation must not be allowed to evict the register `l` just landed in, and
+            // allocating `dst` must not evict either operand, before the `BinOp` instruction reads
+            // them — see `RegAlloc::alloc`'s `protect` parameter.
+            let l = codegen_value(alloc, lhs, out, &[]);
+            let r = codegen_value(alloc, rhs, out, &[l]);
+            let t = alloc.new_temp();
+            let dst = alloc.alloc(t, out, &[l, r]);
+            out.push(Instr::BinOp(binop2(op), dst, l, r));
+            dst
+        }
+        Expr::UnaryOp(op, value, _) => {
+            let v = codegen_value(alloc, value, out, &[]);
+            let t = alloc.new_temp();
+            let dst = alloc.alloc(t, out, &[v]);
+            out.push(Instr::UnaryOp(unaryop2(op), dst, v));
+            dst
+        }
+    }
+}
+
+fn codegen_value(alloc: &mut RegAlloc, value: &Value, out: &mut Vec<Instr>, protect: &[u8]) -> u8 {
+    match value {
+        Value::Var(var, _) => alloc.reg_of(*var, out, protect),
+        Value::Num { value, .. } => {
+            let t = alloc.new_temp();
+            let reg = alloc.alloc(t, out, protect);
+            out.push(Instr::LoadImm(reg, *value));
+            reg
+        }
+        Value::Literal(key, _) => {
+            let t = alloc.new_temp();
+            let reg = alloc.alloc(t, out, protect);
+            out.push(Instr::LoadLiteral(reg, *key));
+            reg
+        }
+        Value::Uninit(_) | Value::Unit(_) => R_ZERO,
+    }
+}
+
+/// A minimal executor for [`CodeUnit`] bytecode: one `i64` per register, plus a stack of spill
+/// slots on which each call gets its own frame (slot numbers are relative to `frame_base`). Function
+/// calls recurse through the host call stack, which is enough since cflat itself has no first-class
+/// continuations to model.
+pub struct Vm<'u, 'a> {
+    code: &'u CodeUnit,
+    program: &'u Program<'a>,
+    regs: [i64; NUM_REGS],
+    stack: Vec<i64>,
+    frame_base: usize,
+}
+
+impl<'u, 'a> Vm<'u, 'a> {
+    pub fn new(code: &'u CodeUnit, program: &'u Program<'a>) -> Self {
+        Vm { code, program, regs: [0; NUM_REGS], stack: Vec::new(), frame_base: 0 }
+    }
+
+    /// Runs the program's `main` function to completion and returns its `r1` result.
+    pub fn run(&mut self) -> i64 {
+        self.exec_function(self.code.entry)
+    }
+
+    fn slot_mut(&mut self, slot: u32) -> &mut i64 {
+        let slot = self.frame_base + slot as usize;
+        if slot >= self.stack.len() {
+            self.stack.resize(slot + 1, 0);
+        }
+        &mut self.stack[slot]
+    }
+
+    fn exec_function(&mut self, key: FuncKey) -> i64 {
+        let instrs = &self.code.functions[&key];
+        let mut pc = 0usize;
+        while pc < instrs.len() {
+            match instrs[pc] {
+                Instr::LoadImm(r, v) => self.regs[r as usize] = v,
+                Instr::LoadLiteral(r, key) => {
+                    self.regs[r as usize] = self.program.literals[key].len() as i64;
+                }
+                Instr::Move(dst, src) => self.regs[dst as usize] = self.regs[src as usize],
+                Instr::BinOp(op, dst, a, b) => {
+                    let (a, b) = (self.regs[a as usize], self.regs[b as usize]);
+                    self.regs[dst as usize] = eval_binop(op, a, b);
+                }
+                Instr::UnaryOp(op, dst, a) => {
+                    let a = self.regs[a as usize];
+                    self.regs[dst as usize] = eval_unaryop(op, a);
+                }
+                Instr::StoreSlot(slot, r) => *self.slot_mut(slot) = self.regs[r as usize],
+                Instr::LoadSlot(r, slot) => self.regs[r as usize] = *self.slot_mut(slot),
+                Instr::Jump(target) => {
+                    pc = target;
+                    continue;
+                }
+                Instr::JumpIfZero(r, target) => {
+                    if self.regs[r as usize] == 0 {
+                        pc = target;
+                        continue;
+                    }
+                }
+                Instr::Call(callee) => {
+                    // The callee's slots start past every slot this frame has touched so far.
+                    let caller_base = self.frame_base;
+                    self.frame_base = self.stack.len();
+                    let result = self.exec_function(callee);
+                    self.stack.truncate(self.frame_base);
+                    self.frame_base = caller_base;
+                    self.regs[R_RET.start as usize] = result;
+                }
+                Instr::Return => return self.regs[R_RET.start as usize],
+            }
+            pc += 1;
+        }
+        self.regs[R_RET.start as usize]
+    }
+}
+
+fn eval_binop(op: BinOp2, a: i64, b: i64) -> i64 {
+    match op {
+        BinOp2::Add => a.wrapping_add(b),
+        BinOp2::Sub => a.wrapping_sub(b),
+        BinOp2::Mul => a.wrapping_mul(b),
+        BinOp2::Div => if b == 0 { 0 } else { a / b },
+        BinOp2::Mod => if b == 0 { 0 } else { a % b },
+        BinOp2::And => a & b,
+        BinOp2::Or => a | b,
+        BinOp2::Xor => a ^ b,
+        BinOp2::LogicAnd => ((a != 0) && (b != 0)) as i64,
+        BinOp2::LogicOr => ((a != 0) || (b != 0)) as i64,
+        BinOp2::LogicXor => ((a != 0) != (b != 0)) as i64,
+        BinOp2::Eq => (a == b) as i64,
+        BinOp2::Ne => (a != b) as i64,
+        BinOp2::Gt => (a > b) as i64,
+        BinOp2::Ge => (a >= b) as i64,
+        BinOp2::Lt => (a < b) as i64,
+        BinOp2::Le => (a <= b) as i64,
+    }
+}
+
+fn eval_unaryop(op: UnaryOp2, a: i64) -> i64 {
+    match op {
+        UnaryOp2::AddressOf | UnaryOp2::Deref => a,
+        UnaryOp2::Negate => -a,
+        UnaryOp2::Not => (a == 0) as i64,
+    }
+}
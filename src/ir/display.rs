@@ -0,0 +1,157 @@
+use std::fmt;
+
+use super::{Block, DirectType, Expr, Function, Program, Statement, Type, Value};
+
+impl fmt::Display for Program<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, key) in &self.function_names {
+            writeln!(f, "fn {name}:")?;
+            self.functions[*key].fmt_indented(f, 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn indent(f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        write!(f, "    ")?;
+    }
+    Ok(())
+}
+
+impl Function<'_> {
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        self.body.fmt_indented(f, depth)
+    }
+}
+
+impl Block<'_> {
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        for stmt in &self.stmts {
+            stmt.fmt_indented(f, depth)?;
+        }
+        Ok(())
+    }
+}
+
+impl Statement<'_> {
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        indent(f, depth)?;
+        match self {
+            Statement::Assign(var, expr, _) => writeln!(f, "{var:?} = {expr}"),
+            Statement::DerefAssign(ptr, value, _) => writeln!(f, "*{ptr} = {value}"),
+            Statement::FieldAssign { object, field, value, .. } => writeln!(f, "{object}.{field} = {value}"),
+            Statement::Do(expr) => writeln!(f, "{expr}"),
+            Statement::Block(block, _) => {
+                writeln!(f, "{{")?;
+                block.fmt_indented(f, depth + 1)?;
+                indent(f, depth)?;
+                writeln!(f, "}}")
+            }
+            Statement::If { cond, block, else_block, .. } => {
+                writeln!(f, "if {cond} {{")?;
+                block.fmt_indented(f, depth + 1)?;
+                indent(f, depth)?;
+                if let Some(else_block) = else_block {
+                    writeln!(f, "}} else {{")?;
+                    else_block.fmt_indented(f, depth + 1)?;
+                    indent(f, depth)?;
+                }
+                writeln!(f, "}}")
+            }
+            Statement::Loop(block, _) => {
+                writeln!(f, "loop {{")?;
+                block.fmt_indented(f, depth + 1)?;
+                indent(f, depth)?;
+                writeln!(f, "}}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Var(var, _) => write!(f, "{var:?}"),
+            Value::Num { value, .. } => write!(f, "{value}"),
+            Value::Literal(key, _) => write!(f, "{key:?}"),
+            Value::Uninit(_) => write!(f, "---"),
+            Value::Unit(_) => write!(f, "()"),
+        }
+    }
+}
+
+impl fmt::Display for Expr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Value(v) => write!(f, "{v}"),
+            Expr::FieldAccess(object, field, _) => write!(f, "{object}.{field}"),
+            Expr::PathAccess(key, field, _) => write!(f, "{key:?}::{field}"),
+            Expr::FuncCall(key, args, _) => {
+                write!(f, "{key:?}(")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                write!(f, ")")
+            }
+            Expr::StructLiteral { ty, fields, .. } => {
+                write!(f, "{ty:?} {{ ")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{name} = {value}")?;
+                }
+                write!(f, " }}")
+            }
+            Expr::Return(value, _) => match value {
+                Some(v) => write!(f, "return {v}"),
+                None => write!(f, "return"),
+            },
+            Expr::Break(_) => write!(f, "break"),
+            Expr::Continue(_) => write!(f, "continue"),
+            Expr::BinOp(lhs, op, rhs, _) => write!(f, "({lhs} {op:?} {rhs})"),
+            Expr::UnaryOp(op, value, _) => write!(f, "({op:?} {value})"),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Direct(key) => write!(f, "{key:?}"),
+            Type::Primitive(p) => write!(f, "{p:?}"),
+            Type::Uninit => write!(f, "---"),
+            Type::Unit => write!(f, "void"),
+            Type::Never => write!(f, "!"),
+            Type::Undeclared => write!(f, "<undeclared>"),
+            Type::Ptr(inner) => write!(f, "{inner}*"),
+            Type::Slice(inner) => write!(f, "{inner}[]"),
+            Type::Array { ty, len } => write!(f, "{ty}[{len}]"),
+            Type::Func { ret, params } => {
+                write!(f, "fn(")?;
+                for (i, p) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{p}")?;
+                }
+                write!(f, ") -> {ret}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for DirectType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DirectType::Struct { fields } => write!(f, "struct {{ {} fields }}", fields.len()),
+            DirectType::Union { variants } => write!(f, "union {{ {} variants }}", variants.len()),
+            DirectType::Enum { variants } => write!(f, "enum {{ {} variants }}", variants.len()),
+            DirectType::Type(ty) => write!(f, "{ty}"),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! A tree-walking interpreter for a typechecked [`Program`], used by the REPL (see `main.rs`) to
+//! actually run what gets typed in rather than just print the lowered IR.
+//!
+//! Values are represented as plain `i64`s (wide enough for every sized integer this language has);
+//! pointers are indices into a simple `Vec<i64>` heap rather than real addresses.
+
+use slotmap::SecondaryMap;
+
+use super::{BinOp, Block, Expr, FuncKey, Program, Statement, UnaryOp, Value, Var};
+
+pub type Val = i64;
+
+/// The result of evaluating a statement or expression: either a plain value, or one of the three
+/// ways control flow can unwind out of the structure it's nested in.
+enum Flow {
+    Normal(Val),
+    Break,
+    Continue,
+    Return(Val),
+}
+
+impl Flow {
+    fn into_val(self) -> Val {
+        match self {
+            Flow::Normal(v) | Flow::Return(v) => v,
+            Flow::Break | Flow::Continue => 0,
+        }
+    }
+}
+
+/// Local bindings for one in-flight function call.
+type Env = SecondaryMap<Var, Val>;
+
+pub struct Interp<'p, 'a> {
+    program: &'p Program<'a>,
+    heap: Vec<Val>,
+}
+
+impl<'p, 'a> Interp<'p, 'a> {
+    pub fn new(program: &'p Program<'a>) -> Self {
+        Interp { program, heap: Vec::new() }
+    }
+
+    /// Calls `main` with no arguments and returns its result.
+    pub fn run_main(&mut self) -> Val {
+        let Some(&main) = self.program.function_names.get("main") else {
+            return 0;
+        };
+        self.call(main, &[])
+    }
+
+    pub fn call(&mut self, key: FuncKey, args: &[Val]) -> Val {
+        let func = &self.program.functions[key];
+        let mut env = Env::new();
+        for (&var, &value) in func.param_vars.iter().zip(args) {
+            env.insert(var, value);
+        }
+        self.exec_block(&func.body, &mut env).into_val()
+    }
+
+    fn exec_block(&mut self, block: &Block<'a>, env: &mut Env) -> Flow {
+        let mut last = Flow::Normal(0);
+        for stmt in &block.stmts {
+            last = self.exec_stmt(stmt, env);
+            if !matches!(last, Flow::Normal(_)) {
+                return last;
+            }
+        }
+        last
+    }
+
+    fn exec_stmt(&mut self, stmt: &Statement<'a>, env: &mut Env) -> Flow {
+        match stmt {
+            Statement::Assign(var, expr, _) => match self.eval_expr(expr, env) {
+                Flow::Normal(v) => {
+                    env.insert(*var, v);
+                    Flow::Normal(v)
+                }
+                flow => flow,
+            },
+            Statement::DerefAssign(ptr, value, _) => {
+                let addr = match self.eval_expr(ptr, env) {
+                    Flow::Normal(v) => v,
+                    flow => return flow,
+                };
+                let value = match self.eval_expr(value, env) {
+                    Flow::Normal(v) => v,
+                    flow => return flow,
+                };
+                if let Some(slot) = self.heap.get_mut(addr as usize) {
+                    *slot = value;
+                }
+                Flow::Normal(value)
+            }
+            // Fields aren't given a memory layout by the interpreter yet; evaluate for side effects.
+            Statement::FieldAssign { value, .. } => self.eval_expr(value, env),
+            Statement::Do(expr) => self.eval_expr(expr, env),
+            Statement::Block(block, _) => self.exec_block(block, env),
+            Statement::If { cond, block, else_block, .. } => {
+                let cond = match self.eval_expr(cond, env) {
+                    Flow::Normal(v) => v,
+                    flow => return flow,
+                };
+                if cond != 0 {
+                    self.exec_block(block, env)
+                } else if let Some(else_block) = else_block {
+                    self.exec_block(else_block, env)
+                } else {
+                    Flow::Normal(0)
+                }
+            }
+            Statement::Loop(block, _) => loop {
+                match self.exec_block(block, env) {
+                    Flow::Break => return Flow::Normal(0),
+                    Flow::Return(v) => return Flow::Return(v),
+                    Flow::Normal(_) | Flow::Continue => {}
+                }
+            },
+        }
+    }
+
+    fn eval_expr(&mut self, expr: &Expr<'a>, env: &mut Env) -> Flow {
+        match expr {
+            Expr::Value(v) => Flow::Normal(self.eval_value(v, env)),
+            Expr::FieldAccess(object, _, _) => Flow::Normal(self.eval_value(object, env)),
+            Expr::PathAccess(_, _, _) => Flow::Normal(0),
+            Expr::FuncCall(key, args, _) => {
+                let args: Vec<Val> = args.iter().map(|a| self.eval_value(a, env)).collect();
+                Flow::Normal(self.call(*key, &args))
+            }
+            Expr::StructLiteral { fields, .. } => {
+                for (_, value) in fields {
+                    self.eval_value(value, env);
+                }
+                Flow::Normal(0)
+            }
+            Expr::Return(value, _) => Flow::Return(value.as_ref().map(|v| self.eval_value(v, env)).unwrap_or(0)),
+            Expr::Break(_) => Flow::Break,
+            Expr::Continue(_) => Flow::Continue,
+            Expr::BinOp(lhs, op, rhs, _) => {
+                let lhs = self.eval_value(lhs, env);
+                let rhs = self.eval_value(rhs, env);
+                Flow::Normal(eval_binop(op, lhs, rhs))
+            }
+            Expr::UnaryOp(op, value, _) => Flow::Normal(self.eval_unaryop(op, value, env)),
+        }
+    }
+
+    fn eval_value(&mut self, value: &Value, env: &Env) -> Val {
+        match value {
+            Value::Var(var, _) => env.get(*var).copied().unwrap_or(0),
+            Value::Num { value, .. } => *value,
+            Value::Literal(key, _) => self.program.literals[*key].len() as i64,
+            Value::Uninit(_) => 0,
+            Value::Unit(_) => 0,
+        }
+    }
+
+    fn eval_unaryop(&mut self, op: &UnaryOp, value: &Value, env: &Env) -> Val {
+        match op {
+            UnaryOp::AddressOf => {
+                let v = self.eval_value(value, env);
+                self.heap.push(v);
+                (self.heap.len() - 1) as i64
+            }
+            UnaryOp::Deref => {
+                let addr = self.eval_value(value, env);
+                self.heap.get(addr as usize).copied().unwrap_or(0)
+            }
+            UnaryOp::Negate => -self.eval_value(value, env),
+            UnaryOp::Not => (self.eval_value(value, env) == 0) as i64,
+        }
+    }
+}
+
+fn eval_binop(op: &BinOp, a: Val, b: Val) -> Val {
+    match op {
+        BinOp::Add => a.wrapping_add(b),
+        BinOp::Sub => a.wrapping_sub(b),
+        BinOp::Mul => a.wrapping_mul(b),
+        BinOp::Div => if b == 0 { 0 } else { a / b },
+        BinOp::Mod => if b == 0 { 0 } else { a % b },
+        BinOp::And => a & b,
+        BinOp::Or => a | b,
+        BinOp::Xor => a ^ b,
+        BinOp::LogicAnd => ((a != 0) && (b != 0)) as i64,
+        BinOp::LogicOr => ((a != 0) || (b != 0)) as i64,
+        BinOp::LogicXor => ((a != 0) != (b != 0)) as i64,
+        BinOp::Eq => (a == b) as i64,
+        BinOp::Ne => (a != b) as i64,
+        BinOp::Gt => (a > b) as i64,
+        BinOp::Ge => (a >= b) as i64,
+        BinOp::Lt => (a < b) as i64,
+        BinOp::Le => (a <= b) as i64,
+    }
+}
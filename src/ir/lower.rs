@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use chumsky::error::Rich;
+
+use crate::ast::{self, Span};
+
+use super::{BinOp, Block, DirectType, Expr, Function, FunctionDecl, Param, PrimitiveType, Program, Statement, Type, UnaryOp, Value, Variable};
+
+type LowerError<'a> = Rich<'a, String, Span>;
+
+fn err<'a, T>(span: Span, msg: impl Into<String>) -> Result<T, LowerError<'a>> {
+    Err(Rich::custom(span, msg.into()))
+}
+
+impl<'a> Program<'a> {
+    /// Lowers a parsed translation unit into the IR, resolving every name to a [`super::TypeKey`]/[`super::FuncKey`]
+    /// and every variable reference to a [`super::Var`] local to its enclosing [`Function`].
+    pub fn lower(items: &[ast::Item<'a>]) -> Result<Program<'a>, LowerError<'a>> {
+        let mut program = Program::default();
+
+        // Pass 1: register every named type and function so forward references resolve.
+        for item in items {
+            match item {
+                ast::Item::Struct { name, span, .. } | ast::Item::Union { name, span, .. } | ast::Item::Enum { name, span, .. } => {
+                    if program.type_decls.contains_key(name) {
+                        return err(*span, format!("type `{name}` declared twice"));
+                    }
+                    let key = program.types.insert(DirectType::Type(Type::Undeclared));
+                    program.type_decls.insert(name, key);
+                }
+                ast::Item::Function(_) => {}
+            }
+        }
+        for item in items {
+            if let ast::Item::Function(f) = item {
+                if program.function_names.contains_key(f.name) {
+                    return err(f.span, format!("function `{}` declared twice", f.name));
+                }
+                let decl = FunctionDecl {
+                    ret: lower_type(&program, &f.ret)?,
+                    params: f
+                        .params
+                        .iter()
+                        .map(|p| {
+                            Ok(Param { outward_name: p.outward_name, name: p.name, ty: lower_type(&program, &p.ty)? })
+                        })
+                        .collect::<Result<_, LowerError>>()?,
+                };
+                let key = program.functions.insert(Function::default());
+                program.function_decls.insert(key, decl);
+                program.function_names.insert(f.name, key);
+            }
+        }
+
+        // Pass 2: fill in type bodies and function bodies now that every name resolves.
+        for item in items {
+            match item {
+                ast::Item::Struct { name, fields, .. } => {
+                    let key = program.type_decls[name];
+                    let fields = fields.iter().map(|(n, t)| Ok((*n, lower_type(&program, t)?))).collect::<Result<_, LowerError>>()?;
+                    program.types[key] = DirectType::Struct { fields };
+                }
+                ast::Item::Union { name, variants, .. } => {
+                    let key = program.type_decls[name];
+                    let variants = variants.iter().map(|(n, t)| Ok((*n, lower_type(&program, t)?))).collect::<Result<_, LowerError>>()?;
+                    program.types[key] = DirectType::Union { variants };
+                }
+                ast::Item::Enum { name, variants, .. } => {
+                    let key = program.type_decls[name];
+                    let mut next = 0i32;
+                    let variants = variants
+                        .iter()
+                        .map(|(n, v)| {
+                            let v = v.unwrap_or(next as i64) as i32;
+                            next = v + 1;
+                            (*n, v)
+                        })
+                        .collect();
+                    program.types[key] = DirectType::Enum { variants };
+                }
+                ast::Item::Function(f) => {
+                    let key = program.function_names[f.name];
+                    let mut lowerer = FnLowerer { program: &mut program, vars: HashMap::new(), func: Function::default() };
+                    for p in &f.params {
+                        let ty = lower_type(lowerer.program, &p.ty)?;
+                        let var = lowerer.func.variables.insert(Variable { ty });
+                        lowerer.vars.insert(p.name, var);
+                        lowerer.func.param_vars.push(var);
+                    }
+                    let body = lowerer.lower_block(&f.body)?;
+                    let mut func = lowerer.func;
+                    func.body = body;
+                    program.functions[key] = func;
+                }
+            }
+        }
+
+        Ok(program)
+    }
+}
+
+fn lower_type<'a>(program: &Program<'a>, ty: &ast::Type<'a>) -> Result<Type, LowerError<'a>> {
+    Ok(match ty {
+        ast::Type::Void => Type::Unit,
+        ast::Type::Primitive(p) => Type::Primitive(match p {
+            ast::Prim::I8 => PrimitiveType::I8,
+            ast::Prim::I16 => PrimitiveType::I16,
+            ast::Prim::I32 => PrimitiveType::I32,
+            ast::Prim::I64 => PrimitiveType::I64,
+            ast::Prim::U8 => PrimitiveType::U8,
+            ast::Prim::U16 => PrimitiveType::U16,
+            ast::Prim::U32 => PrimitiveType::U32,
+            ast::Prim::U64 => PrimitiveType::U64,
+            ast::Prim::F32 => PrimitiveType::F32,
+            ast::Prim::Bool => PrimitiveType::Bool,
+        }),
+        ast::Type::Named(name) => match program.type_decls.get(name) {
+            Some(key) => Type::Direct(*key),
+            None => return err(Span::new((), 0..0), format!("unknown type `{name}`")),
+        },
+        ast::Type::Ptr(inner) => Type::Ptr(Box::new(lower_type(program, inner)?)),
+        ast::Type::Slice(inner) => Type::Slice(Box::new(lower_type(program, inner)?)),
+        ast::Type::Array(inner, len) => Type::Array { ty: Box::new(lower_type(program, inner)?), len: *len },
+    })
+}
+
+struct FnLowerer<'a, 'p> {
+    program: &'p mut Program<'a>,
+    vars: HashMap<&'a str, super::Var>,
+    func: Function<'a>,
+}
+
+impl<'a> FnLowerer<'a, '_> {
+    fn lower_block(&mut self, stmts: &[ast::Stmt<'a>]) -> Result<Block<'a>, LowerError<'a>> {
+        let mut block = Block::default();
+        for stmt in stmts {
+            self.lower_stmt(stmt, &mut block.stmts)?;
+        }
+        Ok(block)
+    }
+
+    /// Lowers one statement into `out`, the statement list of the block it belongs to. Expressions
+    /// are lowered into `out` too (rather than a throwaway buffer) so that any temporaries a nested
+    /// compound expression hoists (see [`Self::lower_value`]) end up defined, in order, right before
+    /// the statement that reads them.
+    fn lower_stmt(&mut self, stmt: &ast::Stmt<'a>, out: &mut Vec<Statement<'a>>) -> Result<(), LowerError<'a>> {
+        match stmt {
+            ast::Stmt::Assign { name, ty, value, span } => {
+                let value = self.lower_expr(value, out)?;
+                let declared = ty.as_ref().map(|t| lower_type(self.program, t)).transpose()?;
+                let var = match self.vars.get(name) {
+                    Some(&var) => var,
+                    None => {
+                        let var = self.func.variables.insert(Variable { ty: declared.unwrap_or(Type::Undeclared) });
+                        self.vars.insert(name, var);
+                        var
+                    }
+                };
+                out.push(Statement::Assign(var, value, *span));
+            }
+            ast::Stmt::DerefAssign { ptr, value, span } => {
+                let ptr = self.lower_expr(ptr, out)?;
+                let value = self.lower_expr(value, out)?;
+                out.push(Statement::DerefAssign(ptr, value, *span));
+            }
+            ast::Stmt::FieldAssign { object, field, value, span } => {
+                let object = self.lower_expr(object, out)?;
+                let value = self.lower_expr(value, out)?;
+                out.push(Statement::FieldAssign { object, field, value, span: *span });
+            }
+            ast::Stmt::Do(expr) => {
+                let expr = self.lower_expr(expr, out)?;
+                out.push(Statement::Do(expr));
+            }
+            ast::Stmt::Block(stmts, span) => {
+                let block = self.lower_block(stmts)?;
+                out.push(Statement::Block(block, *span));
+            }
+            ast::Stmt::If { cond, block, else_block, span } => {
+                let cond = self.lower_expr(cond, out)?;
+                let block = self.lower_block(block)?;
+                let else_block = else_block.as_ref().map(|b| self.lower_block(b)).transpose()?;
+                out.push(Statement::If { cond, block, else_block, span: *span });
+            }
+            ast::Stmt::Loop(stmts, span) => {
+                let block = self.lower_block(stmts)?;
+                out.push(Statement::Loop(block, *span));
+            }
+        }
+        Ok(())
+    }
+
+    /// Lowers an expression down to a [`Value`], hoisting it into a fresh temporary [`Variable`]
+    /// (defined by an `Assign` pushed onto `out`, right before the expression that needs it) if it
+    /// isn't already trivial.
+    fn lower_value(&mut self, expr: &ast::Expr<'a>, out: &mut Vec<Statement<'a>>) -> Result<Value, LowerError<'a>> {
+        match self.lower_expr(expr, out)? {
+            Expr::Value(v) => Ok(v),
+            other => {
+                // Non-trivial sub-expressions get hoisted into a fresh temporary.
+                let span = other.span();
+                let var = self.func.variables.insert(Variable { ty: Type::Undeclared });
+                out.push(Statement::Assign(var, other, span));
+                Ok(Value::Var(var, span))
+            }
+        }
+    }
+
+    fn lower_expr(&mut self, expr: &ast::Expr<'a>, out: &mut Vec<Statement<'a>>) -> Result<Expr<'a>, LowerError<'a>> {
+        Ok(match expr {
+            ast::Expr::Var(name, span) => {
+                let var = *self
+                    .vars
+                    .get(name)
+                    .ok_or_else(|| Rich::custom(*span, format!("undeclared variable `{name}`")))?;
+                Value::Var(var, *span).expr()
+            }
+            &ast::Expr::Num { value, suffix, span } => Value::Num {
+                value,
+                bits: suffix.map(|s| s.bits),
+                signed: suffix.map(|s| s.signed),
+                span,
+            }
+            .expr(),
+            ast::Expr::Str(s, span) => {
+                let key = self.program.literals.insert(s.clone());
+                Value::Literal(key, *span).expr()
+            }
+            ast::Expr::Uninit(span) => Value::Uninit(*span).expr(),
+            ast::Expr::Unit(span) => Value::Unit(*span).expr(),
+            ast::Expr::StructLiteral { ty, fields, span } => {
+                let key = *self
+                    .program
+                    .type_decls
+                    .get(ty)
+                    .ok_or_else(|| Rich::custom(*span, format!("unknown type `{ty}`")))?;
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            Some(v) => self.lower_value(v, out)?,
+                            None => Value::Uninit(*span),
+                        };
+                        Ok((*name, value))
+                    })
+                    .collect::<Result<_, LowerError>>()?;
+                Expr::StructLiteral { ty: key, fields, span: *span }
+            }
+            ast::Expr::FieldAccess(object, field, span) => {
+                let object = self.lower_value(object, out)?;
+                Expr::FieldAccess(object, field, *span)
+            }
+            ast::Expr::PathAccess(ty, field, span) => {
+                let key = *self
+                    .program
+                    .type_decls
+                    .get(ty)
+                    .ok_or_else(|| Rich::custom(*span, format!("unknown type `{ty}`")))?;
+                Expr::PathAccess(key, field, *span)
+            }
+            ast::Expr::Call { name, args, span } => {
+                let key = *self
+                    .program
+                    .function_names
+                    .get(name)
+                    .ok_or_else(|| Rich::custom(*span, format!("unknown function `{name}`")))?;
+                let args = args.iter().map(|(_, v)| self.lower_value(v, out)).collect::<Result<_, _>>()?;
+                Expr::FuncCall(key, args, *span)
+            }
+            ast::Expr::Return(value, span) => {
+                let value = value.as_deref().map(|v| self.lower_value(v, out)).transpose()?;
+                Expr::Return(value, *span)
+            }
+            ast::Expr::Break(span) => Expr::Break(*span),
+            ast::Expr::Continue(span) => Expr::Continue(*span),
+            ast::Expr::BinOp(lhs, op, rhs, span) => {
+                let lhs = self.lower_value(lhs, out)?;
+                let rhs = self.lower_value(rhs, out)?;
+                Expr::BinOp(lhs, lower_binop(*op), rhs, *span)
+            }
+            ast::Expr::UnaryOp(op, value, span) => {
+                let value = self.lower_value(value, out)?;
+                Expr::UnaryOp(lower_unaryop(*op), value, *span)
+            }
+        })
+    }
+}
+
+fn lower_binop(op: ast::BinOp) -> BinOp {
+    match op {
+        ast::BinOp::Add => BinOp::Add,
+        ast::BinOp::Sub => BinOp::Sub,
+        ast::BinOp::Mul => BinOp::Mul,
+        ast::BinOp::Div => BinOp::Div,
+        ast::BinOp::Mod => BinOp::Mod,
+        ast::BinOp::And => BinOp::And,
+        ast::BinOp::Or => BinOp::Or,
+        ast::BinOp::Xor => BinOp::Xor,
+        ast::BinOp::LogicAnd => BinOp::LogicAnd,
+        ast::BinOp::LogicOr => BinOp::LogicOr,
+        ast::BinOp::LogicXor => BinOp::LogicXor,
+        ast::BinOp::Eq => BinOp::Eq,
+        ast::BinOp::Ne => BinOp::Ne,
+        ast::BinOp::Gt => BinOp::Gt,
+        ast::BinOp::Ge => BinOp::Ge,
+        ast::BinOp::Lt => BinOp::Lt,
+        ast::BinOp::Le => BinOp::Le,
+    }
+}
+
+fn lower_unaryop(op: ast::UnaryOp) -> UnaryOp {
+    match op {
+        ast::UnaryOp::AddressOf => UnaryOp::AddressOf,
+        ast::UnaryOp::Deref => UnaryOp::Deref,
+        ast::UnaryOp::Negate => UnaryOp::Negate,
+        ast::UnaryOp::Not => UnaryOp::Not,
+    }
+}
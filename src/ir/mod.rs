@@ -7,18 +7,20 @@ use crate::ast::Span;
 pub mod lower;
 pub mod display;
 pub mod typecheck;
+pub mod codegen;
+pub mod interp;
 
 new_key_type! {
-    pub struct TypeKey; 
-    struct FuncKey;
-    struct LiteralKey;
-    struct Var;
+    pub struct TypeKey;
+    pub(crate) struct FuncKey;
+    pub(crate) struct LiteralKey;
+    pub(crate) struct Var;
 }
 
 #[derive(Default, Debug)]
 pub struct Program<'a> {
     function_names: HashMap<&'a str, FuncKey>,
-    functions: SlotMap<FuncKey, Function<'a>>,
+    pub(crate) functions: SlotMap<FuncKey, Function<'a>>,
     function_decls: SecondaryMap<FuncKey, FunctionDecl<'a>>,
     type_decls: HashMap<&'a str, TypeKey>,
     types: SlotMap<TypeKey, DirectType<'a>>,
@@ -41,7 +43,46 @@ pub enum DirectType<'a> {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PrimitiveType {
-    I32, F32, Bool, U8
+    I8, I16, I32, I64,
+    U8, U16, U32, U64,
+    F32, Bool
+}
+
+impl PrimitiveType {
+    /// Bit width of integer primitives; `None` for `F32`/`Bool`, which aren't suffixable integers.
+    pub(crate) fn bits(&self) -> Option<u32> {
+        match self {
+            PrimitiveType::I8 | PrimitiveType::U8 => Some(8),
+            PrimitiveType::I16 | PrimitiveType::U16 => Some(16),
+            PrimitiveType::I32 | PrimitiveType::U32 => Some(32),
+            PrimitiveType::I64 | PrimitiveType::U64 => Some(64),
+            PrimitiveType::F32 | PrimitiveType::Bool => None,
+        }
+    }
+
+    /// Signedness of integer primitives; `None` for `F32`/`Bool`.
+    pub(crate) fn signed(&self) -> Option<bool> {
+        match self {
+            PrimitiveType::I8 | PrimitiveType::I16 | PrimitiveType::I32 | PrimitiveType::I64 => Some(true),
+            PrimitiveType::U8 | PrimitiveType::U16 | PrimitiveType::U32 | PrimitiveType::U64 => Some(false),
+            PrimitiveType::F32 | PrimitiveType::Bool => None,
+        }
+    }
+
+    /// The primitive with the given width/signedness, used to resolve a literal's inferred type.
+    pub(crate) fn from_bits(bits: u32, signed: bool) -> Option<PrimitiveType> {
+        Some(match (bits, signed) {
+            (8, true) => PrimitiveType::I8,
+            (16, true) => PrimitiveType::I16,
+            (32, true) => PrimitiveType::I32,
+            (64, true) => PrimitiveType::I64,
+            (8, false) => PrimitiveType::U8,
+            (16, false) => PrimitiveType::U16,
+            (32, false) => PrimitiveType::U32,
+            (64, false) => PrimitiveType::U64,
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -84,6 +125,9 @@ struct Param<'a> {
 #[derive(Default, Debug)]
 struct Function<'a> {
     variables: SlotMap<Var, Variable>,
+    /// The [`Var`] bound to each parameter, in declaration order, so callers/backends can bind
+    /// argument values without having to guess at `variables`' iteration order.
+    param_vars: Vec<Var>,
     body: Block<'a>
 }
 
@@ -125,7 +169,14 @@ enum Statement<'a> {
 #[derive(Debug, Clone, Copy)]
 enum Value {
     Var(Var, Span),
-    Num(i32, Span),
+    /// An integer literal. `bits`/`signed` start as `None` (no suffix) and are filled in by
+    /// `typecheck` once the surrounding context (or, failing that, a default of `I32`) pins them down.
+    Num {
+        value: i64,
+        bits: Option<u32>,
+        signed: Option<bool>,
+        span: Span
+    },
     Literal(LiteralKey, Span),
     Uninit(Span),
     Unit(Span)
@@ -137,6 +188,13 @@ enum Expr<'a> {
     FieldAccess(Value, &'a str, Span),
     PathAccess(TypeKey, &'a str, Span),
     FuncCall(FuncKey, Vec<Value>, Span),
+    /// `Name { field = value, ... }`; every field of a `Struct` must appear exactly once (`---` marks
+    /// one explicitly uninitialized), and a `Union` literal must set exactly one variant.
+    StructLiteral {
+        ty: TypeKey,
+        fields: Vec<(&'a str, Value)>,
+        span: Span
+    },
     Return(Option<Value>, Span),
     Break(Span),
     Continue(Span),
@@ -155,6 +213,14 @@ enum BinOp {
     Eq, Ne, Gt, Ge, Lt, Le
 }
 
+impl<'a> Program<'a> {
+    /// Whether a function with this name has been declared, used by the REPL to decide whether
+    /// there's a `main` worth running yet.
+    pub fn has_function(&self, name: &str) -> bool {
+        self.function_names.contains_key(name)
+    }
+}
+
 impl Block<'_> {
     /// panics if the block contains no statements
     fn last_expr_span(&self) -> Span {
@@ -184,7 +250,7 @@ impl Value {
     fn span(&self) -> Span {
         match *self {
             Value::Var(_, span) => span,
-            Value::Num(_, span) => span,
+            Value::Num { span, .. } => span,
             Value::Literal(_, span) => span,
             Value::Uninit(span) => span,
             Value::Unit(span) => span,
@@ -194,7 +260,7 @@ impl Value {
     fn with_span(self, span: Span) -> Self {
         match self {
             Value::Var(v, _) => Value::Var(v, span),
-            Value::Num(n, _) => Value::Num(n, span),
+            Value::Num { value, bits, signed, .. } => Value::Num { value, bits, signed, span },
             Value::Literal(l, _) => Value::Literal(l, span),
             Value::Uninit(_) => Value::Uninit(span),
             Value::Unit(_) => Value::Unit(span),
@@ -209,6 +275,7 @@ impl Expr<'_> {
             &Expr::FieldAccess(_, _, span) => span,
             &Expr::PathAccess(_, _, span) => span,
             &Expr::FuncCall(_, _, span) => span,
+            &Expr::StructLiteral { span, .. } => span,
             &Expr::Return(_, span) => span,
             &Expr::Break(span) => span,
             &Expr::Continue(span) => span,
@@ -0,0 +1,460 @@
+use chumsky::error::Rich;
+
+use crate::ast::Span;
+
+use super::{BinOp, Block, DirectType, Expr, PrimitiveType, Program, Statement, Type, UnaryOp, Value, Var};
+
+pub type TypeError<'a> = Rich<'a, String, Span>;
+
+fn err<'a>(span: Span, msg: impl Into<String>) -> TypeError<'a> {
+    Rich::custom(span, msg.into())
+}
+
+impl<'a> Program<'a> {
+    /// Infers and checks every variable's type, mutating [`super::Variable::ty`] in place as it goes.
+    ///
+    /// Variables are typed by their first assignment; later assignments and uses are checked for
+    /// agreement with that inferred type. Numeric literals without a suffix (e.g. `100` as opposed to
+    /// `100u8`) start out unconstrained and get their width/signedness filled in from whatever context
+    /// they're used in (the assignment target, the other side of a `BinOp`, a call argument's declared
+    /// parameter type), defaulting to `I32` if nothing ever constrains them. Errors are accumulated
+    /// rather than stopping at the first one, so a single call surfaces every problem in the program.
+    pub fn typecheck(&mut self) -> Vec<TypeError<'a>> {
+        let mut errs = Vec::new();
+        let keys: Vec<_> = self.functions.keys().collect();
+        for key in keys {
+            // Take the body out so `Checker` can mutate `self.functions[key].variables` without also
+            // holding a borrow of the statement tree it's walking.
+            let mut body = std::mem::take(&mut self.functions[key].body);
+            let mut checker = Checker { program: self, key, errs: &mut errs };
+            checker.check_block(&mut body);
+            default_unresolved_block(&mut body, &mut errs);
+
+            check_loop_nesting(&mut errs, &body, 0);
+            let diverges = check_reachability(&mut errs, &body);
+            let ret = self.function_decls.get(key).map(|decl| decl.ret.clone()).unwrap_or(Type::Unit);
+            if !diverges && !matches!(ret, Type::Unit | Type::Never) {
+                let span = if body.stmts.is_empty() { Span::new((), 0..0) } else { body.last_expr_span() };
+                errs.push(err(span, "not all control-flow paths return a value"));
+            }
+
+            self.functions[key].body = body;
+
+            for (_, var) in self.functions[key].variables.iter_mut() {
+                if matches!(var.ty, Type::Undeclared) {
+                    var.ty = Type::Primitive(PrimitiveType::I32);
+                }
+            }
+        }
+        errs
+    }
+}
+
+/// Does this statement, on its own, definitely divert control flow away from falling through to
+/// whatever follows it? Only `Return`/`Break`/`Continue` expressions are divergent themselves; a
+/// `Block`/`If`/`Loop` statement's divergence instead depends on what's nested inside it, which is
+/// why those cases are handled by [`check_reachability`] rather than here.
+fn expr_diverges(expr: &Expr) -> bool {
+    matches!(expr, Expr::Return(_, _) | Expr::Break(_) | Expr::Continue(_))
+}
+
+/// Walks a function body in order, flagging any statement reachable only after a divergent one as
+/// unreachable code, and returns whether the block as a whole definitely diverges (so the caller can
+/// tell whether falling off the end of it is possible).
+fn check_reachability<'a>(errs: &mut Vec<TypeError<'a>>, block: &Block<'a>) -> bool {
+    let mut diverged = false;
+    for stmt in &block.stmts {
+        if diverged {
+            errs.push(err(stmt.span(), "unreachable code"));
+        }
+        let this_diverges = match stmt {
+            Statement::Assign(_, expr, _) => expr_diverges(expr),
+            Statement::DerefAssign(ptr, value, _) => expr_diverges(ptr) || expr_diverges(value),
+            Statement::FieldAssign { object, value, .. } => expr_diverges(object) || expr_diverges(value),
+            Statement::Do(expr) => expr_diverges(expr),
+            Statement::Block(block, _) => check_reachability(errs, block),
+            Statement::If { block, else_block, .. } => {
+                let then_diverges = check_reachability(errs, block);
+                match else_block {
+                    Some(else_block) => then_diverges && check_reachability(errs, else_block),
+                    None => false,
+                }
+            }
+            Statement::Loop(block, _) => {
+                let has_break = loop_has_break(block);
+                check_reachability(errs, block);
+                !has_break
+            }
+        };
+        diverged = diverged || this_diverges;
+    }
+    diverged
+}
+
+/// Whether `block` contains a `break` reachable as *its own* loop's break (i.e. not one belonging to
+/// a loop nested inside it, which has already consumed any `break`s in its own body).
+fn loop_has_break(block: &Block) -> bool {
+    block.stmts.iter().any(|stmt| match stmt {
+        Statement::Assign(_, expr, _) | Statement::Do(expr) => matches!(expr, Expr::Break(_)),
+        Statement::DerefAssign(ptr, value, _) => matches!(ptr, Expr::Break(_)) || matches!(value, Expr::Break(_)),
+        Statement::FieldAssign { object, value, .. } => matches!(object, Expr::Break(_)) || matches!(value, Expr::Break(_)),
+        Statement::Block(block, _) => loop_has_break(block),
+        Statement::If { block, else_block, .. } => loop_has_break(block) || else_block.as_ref().is_some_and(loop_has_break),
+        // A nested loop is responsible for its own `break`s; they don't escape to this one.
+        Statement::Loop(_, _) => false,
+    })
+}
+
+/// Rejects `break`/`continue` that don't appear lexically inside a `Loop`, tracking nesting depth as
+/// it walks down into blocks (`depth` is the number of enclosing `Loop`s at this point).
+fn check_loop_nesting<'a>(errs: &mut Vec<TypeError<'a>>, block: &Block<'a>, depth: u32) {
+    for stmt in &block.stmts {
+        match stmt {
+            Statement::Assign(_, expr, _) => check_expr_nesting(errs, expr, depth),
+            Statement::DerefAssign(ptr, value, _) => {
+                check_expr_nesting(errs, ptr, depth);
+                check_expr_nesting(errs, value, depth);
+            }
+            Statement::FieldAssign { object, value, .. } => {
+                check_expr_nesting(errs, object, depth);
+                check_expr_nesting(errs, value, depth);
+            }
+            Statement::Do(expr) => check_expr_nesting(errs, expr, depth),
+            Statement::Block(block, _) => check_loop_nesting(errs, block, depth),
+            Statement::If { cond, block, else_block, .. } => {
+                check_expr_nesting(errs, cond, depth);
+                check_loop_nesting(errs, block, depth);
+                if let Some(else_block) = else_block {
+                    check_loop_nesting(errs, else_block, depth);
+                }
+            }
+            Statement::Loop(block, _) => check_loop_nesting(errs, block, depth + 1),
+        }
+    }
+}
+
+fn check_expr_nesting<'a>(errs: &mut Vec<TypeError<'a>>, expr: &Expr<'a>, depth: u32) {
+    match expr {
+        Expr::Break(span) if depth == 0 => errs.push(err(*span, "`break` outside of a loop")),
+        Expr::Continue(span) if depth == 0 => errs.push(err(*span, "`continue` outside of a loop")),
+        _ => {}
+    }
+}
+
+/// Defaults every still-unconstrained `Num` literal (no suffix, never unified against a sized
+/// context) to `I32`, the last step of numeric-literal inference. Since this pins down a literal's
+/// final width just like `value_type` does from a hint, it runs the same `fits` check so a literal
+/// that only overflows once it's defaulted (rather than from an explicit suffix or hint) still gets
+/// a diagnostic instead of silently wrapping.
+fn default_unresolved_block<'a>(block: &mut Block<'a>, errs: &mut Vec<TypeError<'a>>) {
+    for stmt in &mut block.stmts {
+        default_unresolved_stmt(stmt, errs);
+    }
+}
+
+fn default_unresolved_stmt<'a>(stmt: &mut Statement<'a>, errs: &mut Vec<TypeError<'a>>) {
+    match stmt {
+        Statement::Assign(_, expr, _) => default_unresolved_expr(expr, errs),
+        Statement::DerefAssign(a, b, _) => {
+            default_unresolved_expr(a, errs);
+            default_unresolved_expr(b, errs);
+        }
+        Statement::FieldAssign { object, value, .. } => {
+            default_unresolved_expr(object, errs);
+            default_unresolved_expr(value, errs);
+        }
+        Statement::Do(expr) => default_unresolved_expr(expr, errs),
+        Statement::Block(block, _) => default_unresolved_block(block, errs),
+        Statement::If { cond, block, else_block, .. } => {
+            default_unresolved_expr(cond, errs);
+            default_unresolved_block(block, errs);
+            if let Some(else_block) = else_block {
+                default_unresolved_block(else_block, errs);
+            }
+        }
+        Statement::Loop(block, _) => default_unresolved_block(block, errs),
+    }
+}
+
+fn default_unresolved_value<'a>(value: &mut Value, errs: &mut Vec<TypeError<'a>>) {
+    if let Value::Num { value: n, bits, signed, span } = value {
+        if bits.is_none() {
+            *bits = Some(32);
+            *signed = Some(true);
+            // A suffixed or hinted literal was already range-checked in `value_type`.
+            if !fits(*n, 32, true) {
+                errs.push(err(*span, format!("literal {n} does not fit in a i32")));
+            }
+        }
+    }
+}
+
+fn default_unresolved_expr<'a>(expr: &mut Expr<'a>, errs: &mut Vec<TypeError<'a>>) {
+    match expr {
+        Expr::Value(v) => default_unresolved_value(v, errs),
+        Expr::FieldAccess(v, _, _) => default_unresolved_value(v, errs),
+        Expr::PathAccess(_, _, _) => {}
+        Expr::FuncCall(_, args, _) => args.iter_mut().for_each(|v| default_unresolved_value(v, errs)),
+        Expr::StructLiteral { fields, .. } => fields.iter_mut().for_each(|(_, v)| default_unresolved_value(v, errs)),
+        Expr::Return(v, _) => {
+            if let Some(v) = v {
+                default_unresolved_value(v, errs);
+            }
+        }
+        Expr::Break(_) | Expr::Continue(_) => {}
+        Expr::BinOp(a, _, b, _) => {
+            default_unresolved_value(a, errs);
+            default_unresolved_value(b, errs);
+        }
+        Expr::UnaryOp(_, v, _) => default_unresolved_value(v, errs),
+    }
+}
+
+struct Checker<'a, 'p> {
+    program: &'p mut Program<'a>,
+    key: super::FuncKey,
+    errs: &'p mut Vec<TypeError<'a>>,
+}
+
+impl<'a> Checker<'a, '_> {
+    fn check_block(&mut self, block: &mut Block<'a>) {
+        for stmt in &mut block.stmts {
+            self.check_stmt(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &mut Statement<'a>) {
+        match stmt {
+            Statement::Assign(var, expr, span) => {
+                let hint = self.program.functions[self.key].variables[*var].ty.clone();
+                let hint = if matches!(hint, Type::Undeclared) { None } else { Some(hint) };
+                let ty = self.check_expr(expr, hint.as_ref());
+                self.unify_var(*var, ty, *span);
+            }
+            Statement::DerefAssign(ptr, value, _) => {
+                self.check_expr(ptr, None);
+                self.check_expr(value, None);
+            }
+            Statement::FieldAssign { object, value, .. } => {
+                self.check_expr(object, None);
+                self.check_expr(value, None);
+            }
+            Statement::Do(expr) => {
+                self.check_expr(expr, None);
+            }
+            Statement::Block(block, _) => self.check_block(block),
+            Statement::If { cond, block, else_block, .. } => {
+                self.check_expr(cond, None);
+                self.check_block(block);
+                if let Some(else_block) = else_block {
+                    self.check_block(else_block);
+                }
+            }
+            Statement::Loop(block, _) => self.check_block(block),
+        }
+    }
+
+    fn unify_var(&mut self, var: Var, ty: Type, span: Span) {
+        let existing = self.program.functions[self.key].variables[var].ty.clone();
+        match existing {
+            Type::Undeclared => self.program.functions[self.key].variables[var].ty = ty,
+            existing if !types_compatible(&existing, &ty) => {
+                self.errs.push(err(span, format!("mismatched types: expected {existing:?}, found {ty:?}")));
+            }
+            _ => {}
+        }
+    }
+
+    fn check_expr(&mut self, expr: &mut Expr<'a>, hint: Option<&Type>) -> Type {
+        match expr {
+            Expr::Value(v) => self.value_type(v, hint),
+            Expr::FieldAccess(object, field, field_span) => {
+                let ty = self.value_type(object, None);
+                self.field_type(&ty, field, *field_span)
+            }
+            Expr::PathAccess(key, field, field_span) => self.field_type(&Type::Direct(*key), field, *field_span),
+            Expr::FuncCall(key, args, call_span) => {
+                let param_tys: Vec<Type> = self
+                    .program
+                    .function_decls
+                    .get(*key)
+                    .map(|decl| decl.params.iter().map(|p| p.ty.clone()).collect())
+                    .unwrap_or_default();
+                for (i, arg) in args.iter_mut().enumerate() {
+                    self.value_type(arg, param_tys.get(i));
+                }
+                let Some(decl) = self.program.function_decls.get(*key) else {
+                    self.errs.push(err(*call_span, "call to unknown function"));
+                    return Type::Undeclared;
+                };
+                if decl.params.len() != args.len() {
+                    self.errs.push(err(*call_span, format!("expected {} arguments, found {}", decl.params.len(), args.len())));
+                }
+                decl.ret.clone()
+            }
+            Expr::StructLiteral { ty, fields, span } => self.check_struct_literal(*ty, fields, *span),
+            Expr::Return(value, _) => {
+                if let Some(value) = value {
+                    self.value_type(value, None);
+                }
+                Type::Never
+            }
+            Expr::Break(_) | Expr::Continue(_) => Type::Never,
+            Expr::BinOp(lhs, op, rhs, op_span) => {
+                // Check one side first so an already-constrained peer (e.g. a typed variable) can hint
+                // the other side's unsuffixed literal before it gets its final type.
+                let lhs_ty = self.value_type(lhs, hint);
+                let rhs_ty = self.value_type(rhs, Some(&lhs_ty));
+                let lhs_ty = if matches!(lhs_ty, Type::Undeclared) { self.value_type(lhs, Some(&rhs_ty)) } else { lhs_ty };
+                if !types_compatible(&lhs_ty, &rhs_ty) {
+                    self.errs.push(err(*op_span, format!("binary op {op:?} between mismatched types {lhs_ty:?} and {rhs_ty:?}")));
+                }
+                match op {
+                    BinOp::Eq | BinOp::Ne | BinOp::Gt | BinOp::Ge | BinOp::Lt | BinOp::Le => Type::Primitive(PrimitiveType::Bool),
+                    _ => lhs_ty,
+                }
+            }
+            Expr::UnaryOp(op, value, op_span) => {
+                let ty = self.value_type(value, hint);
+                match op {
+                    UnaryOp::AddressOf => Type::Ptr(Box::new(ty)),
+                    UnaryOp::Deref => match ty {
+                        Type::Ptr(inner) => *inner,
+                        _ => {
+                            self.errs.push(err(*op_span, "cannot dereference a non-pointer value"));
+                            Type::Undeclared
+                        }
+                    },
+                    UnaryOp::Negate | UnaryOp::Not => ty,
+                }
+            }
+        }
+    }
+
+    /// Resolves a `Value`'s type, pinning down an unsuffixed `Num` literal's width/signedness from
+    /// `hint` if one is given, and checking for range overflow once a width is settled.
+    fn value_type(&mut self, value: &mut Value, hint: Option<&Type>) -> Type {
+        match value {
+            Value::Var(var, _) => self.program.functions[self.key].variables[*var].ty.clone(),
+            Value::Num { value: n, bits, signed, span } => {
+                if bits.is_none() {
+                    if let Some(Type::Primitive(p)) = hint {
+                        if let (Some(b), Some(s)) = (p.bits(), p.signed()) {
+                            *bits = Some(b);
+                            *signed = Some(s);
+                        }
+                    }
+                }
+                match (*bits, *signed) {
+                    (Some(b), Some(s)) => {
+                        if !fits(*n, b, s) {
+                            self.errs.push(err(*span, format!("literal {n} does not fit in a {}{b}", if s { "i" } else { "u" })));
+                        }
+                        Type::Primitive(PrimitiveType::from_bits(b, s).expect("checked width"))
+                    }
+                    _ => Type::Undeclared,
+                }
+            }
+            Value::Literal(_, _) => Type::Slice(Box::new(Type::Primitive(PrimitiveType::U8))),
+            Value::Uninit(_) => Type::Uninit,
+            Value::Unit(_) => Type::Unit,
+        }
+    }
+
+    /// Checks a `Name { field = value, ... }` literal: every `Struct` field must be set exactly once
+    /// (`---` counts as setting it, just to an uninitialized value), and a `Union` literal must set
+    /// exactly one variant.
+    fn check_struct_literal(&mut self, ty: super::TypeKey, fields: &mut [(&'a str, Value)], span: Span) -> Type {
+        let Some(def) = self.program.types.get(ty).cloned() else {
+            self.errs.push(err(span, "struct literal names an unknown type"));
+            return Type::Undeclared;
+        };
+        match def {
+            DirectType::Struct { fields: decl_fields } => {
+                let mut seen: Vec<&str> = Vec::new();
+                for (name, value) in fields.iter_mut() {
+                    if seen.contains(name) {
+                        self.errs.push(err(span, format!("field `{name}` initialized twice")));
+                        continue;
+                    }
+                    seen.push(name);
+                    match decl_fields.iter().find(|(n, _)| n == name) {
+                        Some((_, field_ty)) => {
+                            let vty = self.value_type(value, Some(field_ty));
+                            if !matches!(value, Value::Uninit(_)) && !types_compatible(&vty, field_ty) {
+                                self.errs.push(err(span, format!("field `{name}` expected {field_ty:?}, found {vty:?}")));
+                            }
+                        }
+                        None => self.errs.push(err(span, format!("no field `{name}` on this struct"))),
+                    }
+                }
+                for (name, _) in &decl_fields {
+                    if !seen.contains(name) {
+                        self.errs.push(err(span, format!("missing field `{name}`")));
+                    }
+                }
+                Type::Direct(ty)
+            }
+            DirectType::Union { variants } => {
+                if fields.len() != 1 {
+                    self.errs.push(err(span, "a union literal must set exactly one variant"));
+                }
+                for (name, value) in fields.iter_mut() {
+                    match variants.iter().find(|(n, _)| n == name) {
+                        Some((_, field_ty)) => {
+                            self.value_type(value, Some(field_ty));
+                        }
+                        None => self.errs.push(err(span, format!("no variant `{name}` on this union"))),
+                    }
+                }
+                Type::Direct(ty)
+            }
+            DirectType::Enum { .. } | DirectType::Type(_) => {
+                self.errs.push(err(span, "struct literal for a non-struct/union type"));
+                Type::Undeclared
+            }
+        }
+    }
+
+    fn field_type(&mut self, ty: &Type, field: &str, span: Span) -> Type {
+        let Type::Direct(key) = ty else {
+            self.errs.push(err(span, "field access on a non-struct type"));
+            return Type::Undeclared;
+        };
+        match &self.program.types[*key] {
+            DirectType::Struct { fields } | DirectType::Union { variants: fields } => fields
+                .iter()
+                .find(|(name, _)| *name == field)
+                .map(|(_, ty)| ty.clone())
+                .unwrap_or_else(|| {
+                    self.errs.push(err(span, format!("no field `{field}`")));
+                    Type::Undeclared
+                }),
+            DirectType::Enum { .. } | DirectType::Type(_) => {
+                self.errs.push(err(span, "field access on a non-struct type"));
+                Type::Undeclared
+            }
+        }
+    }
+}
+
+/// Does `value` fit in a `bits`-wide integer of the given signedness? Bounds are computed in
+/// `i128` (rather than `i64`) so a 64-bit width's min/max don't themselves overflow, and so that
+/// `u64`'s upper bound isn't misrepresented by wrapping back around through `i64`. `value` holds a
+/// literal's raw bits (literals are never negative), so it is read back as a `u64` magnitude first.
+fn fits(value: i64, bits: u32, signed: bool) -> bool {
+    let value = value as u64 as i128;
+    if signed {
+        let min = -(1i128 << (bits - 1));
+        let max = (1i128 << (bits - 1)) - 1;
+        (min..=max).contains(&value)
+    } else {
+        let max = (1i128 << bits) - 1;
+        (0..=max).contains(&value)
+    }
+}
+
+fn types_compatible(a: &Type, b: &Type) -> bool {
+    matches!((a, b), (Type::Undeclared, _) | (_, Type::Undeclared) | (Type::Never, _) | (_, Type::Never))
+        || std::mem::discriminant(a) == std::mem::discriminant(b)
+}
@@ -0,0 +1,69 @@
+use chumsky::prelude::*;
+
+use crate::ast::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token<'a> {
+    Ident(&'a str),
+    /// A numeric literal together with its raw suffix text, if any (e.g. `100u8` -> `("100", Some("u8"))`).
+    Num(&'a str, Option<&'a str>),
+    Str(String),
+    Ctrl(char),
+    /// Multi-char operators that can't be represented as a single `Ctrl`.
+    Op(&'a str),
+}
+
+impl std::fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Ident(i) => write!(f, "{i}"),
+            Token::Num(n, None) => write!(f, "{n}"),
+            Token::Num(n, Some(s)) => write!(f, "{n}{s}"),
+            Token::Str(s) => write!(f, "{s:?}"),
+            Token::Ctrl(c) => write!(f, "{c}"),
+            Token::Op(o) => write!(f, "{o}"),
+        }
+    }
+}
+
+/// Tokenizes cflat source text into a spanned token stream, ready to be fed into [`crate::ast::parser`].
+pub fn lexer<'a>() -> impl Parser<'a, &'a str, Vec<(Token<'a>, Span)>, extra::Err<Rich<'a, char, Span>>> {
+    let num = text::int(10)
+        .then(just('.').then(text::digits(10)).or_not())
+        .to_slice()
+        .then(text::ident().or_not())
+        .map(|(digits, suffix): (&str, Option<&str>)| Token::Num(digits, suffix));
+
+    let string = just('"')
+        .ignore_then(none_of('"').repeated().to_slice())
+        .then_ignore(just('"'))
+        .map(|s: &str| Token::Str(s.to_string()));
+
+    let op = choice((
+        just("==").to(Token::Op("==")),
+        just("!=").to(Token::Op("!=")),
+        just(">=").to(Token::Op(">=")),
+        just("<=").to(Token::Op("<=")),
+        just("&&").to(Token::Op("&&")),
+        just("||").to(Token::Op("||")),
+        just("^^").to(Token::Op("^^")),
+        just("::").to(Token::Op("::")),
+        just("---").to(Token::Op("---")),
+    ));
+
+    let ctrl = one_of("{}()[];:,.=+-*/%<>!&|^").map(Token::Ctrl);
+
+    let ident = text::ident().map(Token::Ident);
+
+    let token = choice((num, string, op, ident, ctrl));
+
+    let comment = just("//").then(any().and_is(just('\n').not()).repeated()).padded();
+
+    token
+        .map_with(|tok, e| (tok, e.span()))
+        .padded_by(comment.repeated())
+        .padded()
+        .repeated()
+        .collect()
+        .then_ignore(end())
+}
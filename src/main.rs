@@ -1,6 +1,8 @@
+use std::io::{self, BufRead, Write};
+
 use ast::parser;
 use chumsky::{Parser, prelude::Input};
-use lexer::lexer;
+use lexer::{Token, lexer};
 use error::show_errs;
 
 mod lexer;
@@ -8,54 +10,90 @@ mod ast;
 mod ir;
 mod error;
 
+/// Multi-line REPL: accumulates lines until the braces in the lexed input balance (so a partial
+/// `fn`/`struct`/block keeps prompting), then parses, re-lowers and re-typechecks the whole growing
+/// program and, if it now has a `main`, runs it. Input whose braces balance but which is not yet a
+/// complete item is reported as a parse error rather than prompting for more.
 fn main() {
-    let input = r#"
-struct Vec2 {
-    i32 x;
-    i32 y;
-}
+    let stdin = io::stdin();
+    let mut source = String::new();
+    let mut chunk = String::new();
+    let mut depth: i32 = 0;
 
-void print(msg: u8[] str) {}
-
-void main() {
-    i32 i = 0;
     loop {
-        if i >= 100 { break }
+        print!("{}", if depth > 0 { "...     " } else { "cflat > " });
+        io::stdout().flush().ok();
 
-        if i % 15 == 0 { print(msg = "fizzbuzz") }
-        else if i % 3 == 0 { print(msg = "fizz") }
-        else if i % 5 == 0 { print(msg = "buzz") }
-        else { print(msg = "num") }
-    }
-}
-    "#;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        chunk.push_str(&line);
+
+        // Count braces from the lexed token stream rather than the raw text, so a `{`/`}` inside a
+        // string literal or comment doesn't desync the "balanced input" check. If the chunk doesn't
+        // lex cleanly yet (e.g. a string literal left open across the line break), leave `depth`
+        // alone rather than guessing: inside an open block that keeps prompting, while at the top
+        // level the chunk goes on below and its lex errors are reported.
+        if let Some(tokens) = lexer().parse(chunk.as_str()).into_output() {
+            depth = tokens.iter().fold(0i32, |depth, (tok, _)| match tok {
+                Token::Ctrl('{') => depth + 1,
+                Token::Ctrl('}') => depth - 1,
+                _ => depth,
+            });
+        }
+        if depth > 0 {
+            continue;
+        }
 
-    println!("lexing");
-    let (lexed, errs) = lexer().parse(input).into_output_errors();
-    show_errs(input, "stdin", errs);
+        let candidate = format!("{source}{chunk}");
+        chunk.clear();
 
-    let Some(lexed) = lexed else { return };
+        let (lexed, errs) = lexer().parse(&candidate).into_output_errors();
+        if !errs.is_empty() {
+            show_errs(&candidate, "repl", errs);
+            continue;
+        }
+        let Some(lexed) = lexed else { continue };
 
-    println!("parsing");
-    let (parsed, errs) = parser().parse(Input::spanned(&lexed, (input.len()..input.len()).into())).into_output_errors();
-    show_errs(input, "stdin", errs);
+        let (parsed, errs) = parser().parse(Input::spanned(&lexed, (candidate.len()..candidate.len()).into())).into_output_errors();
+        if !errs.is_empty() {
+            show_errs(&candidate, "repl", errs);
+            continue;
+        }
+        let Some(parsed) = parsed else { continue };
 
-    let Some(parsed) = parsed else { return };
-    for parsed in &parsed {
-        println!("{parsed}");
-    }
+        let mut program = match ir::Program::lower(&parsed) {
+            Ok(p) => p,
+            Err(e) => {
+                show_errs(&candidate, "repl", vec![e]);
+                continue;
+            }
+        };
+        let type_errs = program.typecheck();
+        if !type_errs.is_empty() {
+            show_errs(&candidate, "repl", type_errs);
+            continue;
+        }
+
+        if program.has_function("main") {
+            let mut interp = ir::interp::Interp::new(&program);
+            let result = interp.run_main();
 
-    let p = ir::Program::lower(&parsed);
-    let mut p = match p {
-        Ok(p) => p,
-        Err(e) => {
-            show_errs(input, "stdin", vec![e]);
-            return;
+            // Also run the register-machine backend on every input and cross-check it against the
+            // interpreter, so both stay exercised and a divergence between them is surfaced instead
+            // of silently shipping an untested backend.
+            let code = program.codegen();
+            let mut vm = ir::codegen::Vm::new(&code, &program);
+            let vm_result = vm.run();
+            if vm_result != result {
+                eprintln!("warning: codegen backend result ({vm_result}) differs from interpreter ({result})");
+            }
+
+            println!("=> {result}");
         }
-    };
-    let type_errs = p.typecheck();
-    if !type_errs.is_empty() {
-        show_errs(input, "stdin", type_errs);
+
+        // Only commit the candidate source once it's lexed, parsed, typechecked and run cleanly.
+        source = candidate;
     }
-    println!("{p}");
 }